@@ -4,6 +4,8 @@ fn main() {
     cc::Build::new()
         .file("csrc/flanterm.c")
         .file("csrc/backends/fb.c")
+        .file("csrc/flanterm_rs.c")
+        .file("csrc/sixel/sixel.c")
         .include("csrc")
         .include("csrc/backends")
         .pic(true)
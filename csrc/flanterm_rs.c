@@ -0,0 +1,317 @@
+#include <stddef.h>
+#include <stdint.h>
+#include <stdbool.h>
+
+#define FLANTERM_IN_FLANTERM
+
+#include "flanterm.h"
+#include "backends/fb.h"
+#include "flanterm_rs.h"
+
+void *memcpy(void *, const void *, size_t);
+
+static uint32_t convert_colour(struct flanterm_fb_context *ctx, uint32_t colour) {
+    uint32_t r = (colour >> 16) & 0xff;
+    uint32_t g = (colour >> 8) & 0xff;
+    uint32_t b =  colour & 0xff;
+    return (r << ctx->red_mask_shift) | (g << ctx->green_mask_shift) | (b << ctx->blue_mask_shift);
+}
+
+void flanterm_rs_fb_set_ansi_colour(struct flanterm_context *_ctx, size_t index, uint32_t colour) {
+    struct flanterm_fb_context *ctx = (void *)_ctx;
+
+    if (index < 8) {
+        ctx->ansi_colours[index] = convert_colour(ctx, colour);
+    } else if (index < 16) {
+        ctx->ansi_bright_colours[index - 8] = convert_colour(ctx, colour);
+    }
+}
+
+void flanterm_rs_fb_set_default_colours(struct flanterm_context *_ctx,
+    const uint32_t *default_bg, const uint32_t *default_fg,
+    const uint32_t *default_bg_bright, const uint32_t *default_fg_bright) {
+    struct flanterm_fb_context *ctx = (void *)_ctx;
+
+    if (default_bg != NULL) {
+        ctx->default_bg = convert_colour(ctx, *default_bg);
+    }
+    if (default_fg != NULL) {
+        uint32_t fg = convert_colour(ctx, *default_fg);
+        // Keep text that is currently in the default colour in the default colour
+        if (ctx->text_fg == ctx->default_fg) {
+            ctx->text_fg = fg;
+        }
+        ctx->default_fg = fg;
+    }
+    if (default_bg_bright != NULL) {
+        ctx->default_bg_bright = convert_colour(ctx, *default_bg_bright);
+    }
+    if (default_fg_bright != NULL) {
+        ctx->default_fg_bright = convert_colour(ctx, *default_fg_bright);
+    }
+}
+
+void flanterm_rs_get_cursor_pos(struct flanterm_context *ctx, size_t *x, size_t *y) {
+    ctx->get_cursor_pos(ctx, x, y);
+}
+
+void flanterm_rs_set_tab_size(struct flanterm_context *ctx, size_t tab_size) {
+    ctx->tab_size = tab_size;
+}
+
+size_t flanterm_rs_get_tab_size(struct flanterm_context *ctx) {
+    return ctx->tab_size;
+}
+
+void flanterm_rs_delete_lines(struct flanterm_context *ctx, size_t count) {
+    size_t x, y;
+    ctx->get_cursor_pos(ctx, &x, &y);
+    if (y < ctx->scroll_top_margin || y >= ctx->scroll_bottom_margin) {
+        return;
+    }
+
+    // CSI M scrolls the whole region; scroll only the part from the cursor down
+    size_t old_scroll_top_margin = ctx->scroll_top_margin;
+    ctx->scroll_top_margin = y;
+    if (count > ctx->scroll_bottom_margin - y) {
+        count = ctx->scroll_bottom_margin - y;
+    }
+    for (size_t i = 0; i < count; i++) {
+        ctx->scroll(ctx);
+    }
+    ctx->scroll_top_margin = old_scroll_top_margin;
+
+    if (ctx->autoflush) {
+        ctx->double_buffer_flush(ctx);
+    }
+}
+
+void flanterm_rs_scroll(struct flanterm_context *ctx, size_t count, bool down) {
+    size_t height = ctx->scroll_bottom_margin - ctx->scroll_top_margin;
+    if (count > height) {
+        count = height;
+    }
+    for (size_t i = 0; i < count; i++) {
+        if (down) {
+            ctx->revscroll(ctx);
+        } else {
+            ctx->scroll(ctx);
+        }
+    }
+
+    if (ctx->autoflush) {
+        ctx->double_buffer_flush(ctx);
+    }
+}
+
+size_t flanterm_rs_fb_grid_size(struct flanterm_context *ctx) {
+    return ((struct flanterm_fb_context *)ctx)->grid_size;
+}
+
+void flanterm_rs_fb_save_grid(struct flanterm_context *_ctx, void *dest) {
+    struct flanterm_fb_context *ctx = (void *)_ctx;
+
+    // Pending output only reaches the grid on flush
+    _ctx->double_buffer_flush(_ctx);
+    memcpy(dest, ctx->grid, ctx->grid_size);
+}
+
+void flanterm_rs_fb_restore_grid(struct flanterm_context *_ctx, const void *src) {
+    struct flanterm_fb_context *ctx = (void *)_ctx;
+
+    _ctx->double_buffer_flush(_ctx);
+    memcpy(ctx->grid, src, ctx->grid_size);
+    _ctx->full_refresh(_ctx);
+}
+
+void flanterm_rs_fb_get_framebuffer(struct flanterm_context *_ctx, uint32_t **framebuffer,
+    size_t *width, size_t *height, size_t *pitch) {
+    struct flanterm_fb_context *ctx = (void *)_ctx;
+
+    *framebuffer = (uint32_t *)ctx->framebuffer;
+    *width = ctx->width;
+    *height = ctx->height;
+    *pitch = ctx->pitch;
+}
+
+bool flanterm_rs_get_autoflush(struct flanterm_context *ctx) {
+    return ctx->autoflush;
+}
+
+void flanterm_rs_fb_set_framebuffer(struct flanterm_context *ctx, uint32_t *framebuffer) {
+    ((struct flanterm_fb_context *)ctx)->framebuffer = framebuffer;
+}
+
+// Extend bounds (x0, y0, x1, y1) to cover the cell (x, y), if it is on screen
+static void grow_bounds(struct flanterm_context *ctx, size_t *bounds, size_t x, size_t y) {
+    if (x >= ctx->cols || y >= ctx->rows) {
+        return;
+    }
+    if (x < bounds[0]) {
+        bounds[0] = x;
+    }
+    if (y < bounds[1]) {
+        bounds[1] = y;
+    }
+    if (x > bounds[2]) {
+        bounds[2] = x;
+    }
+    if (y > bounds[3]) {
+        bounds[3] = y;
+    }
+}
+
+bool flanterm_rs_fb_flush_bounds(struct flanterm_context *_ctx, size_t *x, size_t *y,
+    size_t *width, size_t *height, uint8_t *cells, size_t cells_len) {
+    struct flanterm_fb_context *ctx = (void *)_ctx;
+    size_t bounds[4] = { SIZE_MAX, SIZE_MAX, 0, 0 };
+
+    // The cells the flush redraws: the queue and both cursor positions
+    for (size_t i = 0; i < ctx->queue_i; i++) {
+        struct flanterm_fb_queue_item *q = &ctx->queue[i];
+        grow_bounds(_ctx, bounds, q->x, q->y);
+        size_t cell = q->y * _ctx->cols + q->x;
+        if (cells != NULL && cell < cells_len) {
+            cells[cell / 8] |= 1 << (cell % 8);
+        }
+    }
+    grow_bounds(_ctx, bounds, ctx->cursor_x, ctx->cursor_y);
+    grow_bounds(_ctx, bounds, ctx->old_cursor_x, ctx->old_cursor_y);
+
+    _ctx->double_buffer_flush(_ctx);
+
+    if (bounds[0] == SIZE_MAX) {
+        return false;
+    }
+    *x = ctx->offset_x + bounds[0] * ctx->glyph_width;
+    *y = ctx->offset_y + bounds[1] * ctx->glyph_height;
+    *width = (bounds[2] - bounds[0] + 1) * ctx->glyph_width;
+    *height = (bounds[3] - bounds[1] + 1) * ctx->glyph_height;
+    return true;
+}
+
+void flanterm_rs_fb_get_cell_geometry(struct flanterm_context *_ctx, size_t *offset_x,
+    size_t *offset_y, size_t *glyph_width, size_t *glyph_height) {
+    struct flanterm_fb_context *ctx = (void *)_ctx;
+
+    *offset_x = ctx->offset_x;
+    *offset_y = ctx->offset_y;
+    *glyph_width = ctx->glyph_width;
+    *glyph_height = ctx->glyph_height;
+}
+
+void flanterm_rs_fb_refresh_rows(struct flanterm_context *_ctx, size_t start, size_t count) {
+    struct flanterm_fb_context *ctx = (void *)_ctx;
+
+    _ctx->double_buffer_flush(_ctx);
+    for (size_t y = start; y < start + count && y < _ctx->rows; y++) {
+        for (size_t x = 0; x < _ctx->cols; x++) {
+            ctx->plot_char(_ctx, &ctx->grid[y * _ctx->cols + x], x, y);
+        }
+    }
+    // Puts the cursor back if it was in one of the rows
+    _ctx->double_buffer_flush(_ctx);
+}
+
+bool flanterm_rs_fb_blank_grid(struct flanterm_context *_ctx, uint32_t *colour) {
+    struct flanterm_fb_context *ctx = (void *)_ctx;
+    uint32_t bg = ctx->text_bg == 0xffffffff ? ctx->default_bg : ctx->text_bg;
+
+    // Anything else would not match what flanterm paints around the grid
+    if (ctx->canvas != NULL || ctx->sixel_canvas != NULL || bg != ctx->default_bg) {
+        return false;
+    }
+
+    for (size_t i = 0; i < _ctx->rows * _ctx->cols; i++) {
+        ctx->grid[i].c = ' ';
+        ctx->grid[i].fg = ctx->text_fg;
+        ctx->grid[i].bg = ctx->text_bg;
+        ctx->map[i] = NULL;
+    }
+    ctx->queue_i = 0;
+    *colour = bg;
+    return true;
+}
+
+#define GLYPH_SHADOW_SLOTS 16
+
+// A context drawing glyph shadows, with the functions its own were replaced by
+struct glyph_shadow {
+    struct flanterm_context *ctx;
+    void (*plot_char)(struct flanterm_context *ctx, struct flanterm_fb_char *c, size_t x, size_t y);
+    void (*deinit)(struct flanterm_context *ctx);
+    int32_t dx, dy;
+    uint32_t colour;
+};
+
+static struct glyph_shadow glyph_shadows[GLYPH_SHADOW_SLOTS];
+
+static struct glyph_shadow *find_glyph_shadow(struct flanterm_context *ctx) {
+    for (size_t i = 0; i < GLYPH_SHADOW_SLOTS; i++) {
+        if (glyph_shadows[i].ctx == ctx) {
+            return &glyph_shadows[i];
+        }
+    }
+    return NULL;
+}
+
+static bool glyph_pixel_set(struct flanterm_fb_context *ctx, bool *glyph, long gx, long gy) {
+    if (gx < 0 || gy < 0 || (size_t)gx >= ctx->glyph_width || (size_t)gy >= ctx->glyph_height) {
+        return false;
+    }
+    return glyph[(gy / ctx->font_scale_y) * ctx->font_width + gx / ctx->font_scale_x];
+}
+
+static void plot_char_shadow(struct flanterm_context *_ctx, struct flanterm_fb_char *c, size_t x, size_t y) {
+    struct flanterm_fb_context *ctx = (void *)_ctx;
+    struct glyph_shadow *shadow = find_glyph_shadow(_ctx);
+
+    shadow->plot_char(_ctx, c, x, y);
+    if (x >= _ctx->cols || y >= _ctx->rows || c->fg == c->bg) {
+        return;
+    }
+
+    // Paint the background pixels the glyph would cover if moved by (dx, dy),
+    // staying inside the cell so neighbouring cells are left alone
+    bool *glyph = &ctx->font_bool[c->c * ctx->font_height * ctx->font_width];
+    x = ctx->offset_x + x * ctx->glyph_width;
+    y = ctx->offset_y + y * ctx->glyph_height;
+    for (size_t gy = 0; gy < ctx->glyph_height; gy++) {
+        volatile uint32_t *fb_line = ctx->framebuffer + x + (y + gy) * (ctx->pitch / 4);
+        for (size_t gx = 0; gx < ctx->glyph_width; gx++) {
+            if (!glyph_pixel_set(ctx, glyph, gx, gy)
+             && glyph_pixel_set(ctx, glyph, (long)gx - shadow->dx, (long)gy - shadow->dy)) {
+                fb_line[gx] = shadow->colour;
+            }
+        }
+    }
+}
+
+static void deinit_shadow(struct flanterm_context *ctx) {
+    struct glyph_shadow *shadow = find_glyph_shadow(ctx);
+    void (*deinit)(struct flanterm_context *) = shadow->deinit;
+
+    shadow->ctx = NULL;
+    deinit(ctx);
+}
+
+bool flanterm_rs_fb_set_glyph_shadow(struct flanterm_context *_ctx, int32_t dx, int32_t dy, uint32_t colour) {
+    struct flanterm_fb_context *ctx = (void *)_ctx;
+    struct glyph_shadow *shadow = find_glyph_shadow(_ctx);
+
+    if (shadow == NULL) {
+        shadow = find_glyph_shadow(NULL);
+        if (shadow == NULL) {
+            return false;
+        }
+        shadow->ctx = _ctx;
+        shadow->plot_char = ctx->plot_char;
+        shadow->deinit = _ctx->deinit;
+        ctx->plot_char = plot_char_shadow;
+        _ctx->deinit = deinit_shadow;
+    }
+    shadow->dx = dx;
+    shadow->dy = dy;
+    shadow->colour = convert_colour(ctx, colour);
+    return true;
+}
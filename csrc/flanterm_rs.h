@@ -0,0 +1,66 @@
+#ifndef FLANTERM_RS_H
+#define FLANTERM_RS_H 1
+
+#include <stddef.h>
+#include <stdint.h>
+#include <stdbool.h>
+
+#include "flanterm.h"
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* Helpers used by flanterm-rs that reach into the framebuffer backend's
+ * private state. Every ctx passed here must come from flanterm_fb_init(). */
+
+/* Replace ANSI colour `index` (0-7 normal, 8-15 bright) with 0x00RRGGBB `colour`. */
+void flanterm_rs_fb_set_ansi_colour(struct flanterm_context *ctx, size_t index, uint32_t colour);
+/* Replace the default colours; null pointers leave the colour unchanged. */
+void flanterm_rs_fb_set_default_colours(struct flanterm_context *ctx,
+    const uint32_t *default_bg, const uint32_t *default_fg,
+    const uint32_t *default_bg_bright, const uint32_t *default_fg_bright);
+/* Read the 0-indexed cursor column and row. */
+void flanterm_rs_get_cursor_pos(struct flanterm_context *ctx, size_t *x, size_t *y);
+/* Set the tab stop interval; tab_size must not be 0. */
+void flanterm_rs_set_tab_size(struct flanterm_context *ctx, size_t tab_size);
+/* Get the tab stop interval. */
+size_t flanterm_rs_get_tab_size(struct flanterm_context *ctx);
+/* Delete count lines at the cursor row, pulling the rest of the scroll region up. */
+void flanterm_rs_delete_lines(struct flanterm_context *ctx, size_t count);
+/* Scroll the scroll region up (or down) by count lines without moving the cursor. */
+void flanterm_rs_scroll(struct flanterm_context *ctx, size_t count, bool down);
+/* Size in bytes of the character grid copied by the two functions below. */
+size_t flanterm_rs_fb_grid_size(struct flanterm_context *ctx);
+/* Copy the on-screen characters to dest. */
+void flanterm_rs_fb_save_grid(struct flanterm_context *ctx, void *dest);
+/* Replace the on-screen characters with a copy saved by flanterm_rs_fb_save_grid and redraw. */
+void flanterm_rs_fb_restore_grid(struct flanterm_context *ctx, const void *src);
+/* Read the framebuffer flanterm draws to, its size in pixels and its pitch in bytes. */
+void flanterm_rs_fb_get_framebuffer(struct flanterm_context *ctx, uint32_t **framebuffer,
+    size_t *width, size_t *height, size_t *pitch);
+/* Get the autoflush setting. */
+bool flanterm_rs_get_autoflush(struct flanterm_context *ctx);
+/* Point flanterm at another buffer with the same size and pitch as its framebuffer. */
+void flanterm_rs_fb_set_framebuffer(struct flanterm_context *ctx, uint32_t *framebuffer);
+/* Flush, reporting the pixel rectangle redrawn; returns false if nothing was. If cells is
+ * not NULL, bit y * cols + x of it is also set for each changed cell below cells_len. */
+bool flanterm_rs_fb_flush_bounds(struct flanterm_context *ctx, size_t *x, size_t *y,
+    size_t *width, size_t *height, uint8_t *cells, size_t cells_len);
+/* Read where the character grid starts in the framebuffer and the size of one cell in pixels. */
+void flanterm_rs_fb_get_cell_geometry(struct flanterm_context *ctx, size_t *offset_x,
+    size_t *offset_y, size_t *glyph_width, size_t *glyph_height);
+/* Flush, then redraw count rows of the character grid from row start. */
+void flanterm_rs_fb_refresh_rows(struct flanterm_context *ctx, size_t start, size_t count);
+/* Blank every cell without drawing, for the caller to fill the framebuffer with *colour.
+ * Returns false and changes nothing unless the background is the plain default colour. */
+bool flanterm_rs_fb_blank_grid(struct flanterm_context *ctx, uint32_t *colour);
+/* Draw a copy of every glyph dx, dy pixels away in 0x00RRGGBB colour, beneath the glyph and
+ * clipped to its cell. Returns false if too many contexts have a shadow already. */
+bool flanterm_rs_fb_set_glyph_shadow(struct flanterm_context *ctx, int32_t dx, int32_t dy, uint32_t colour);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
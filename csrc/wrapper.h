@@ -1,2 +1,3 @@
 #include "flanterm.h"
-#include "backends/fb.h"
\ No newline at end of file
+#include "backends/fb.h"
+#include "flanterm_rs.h"
\ No newline at end of file
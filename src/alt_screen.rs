@@ -0,0 +1,97 @@
+use crate::FlantermContext;
+use crate::bindings::*;
+use core::ffi::c_void;
+use core::fmt::Write;
+use core::ptr;
+
+/// Main screen contents saved while the alternate screen is shown
+pub(crate) struct SavedScreen {
+    /// Copy of flanterm's character grid, or null if it could not be saved
+    grid: *mut c_void,
+    cursor: (usize, usize),
+}
+
+impl FlantermContext {
+    /// Switch to a blank alternate screen, saving the main one (mode 1049)
+    ///
+    /// flanterm implements neither mode 1049 nor 47, so the wrapper saves
+    /// the main screen itself. That needs a buffer from the context's
+    /// [`allocator`](crate::FlantermContextBuilder::allocator); without one
+    /// the alternate screen still works, but leaving it just clears the
+    /// screen instead of bringing the old contents back. The cursor position
+    /// is restored either way. Does nothing if already on the alternate screen.
+    pub fn enter_alt_screen(&mut self) {
+        if self.main_screen.is_some() {
+            return;
+        }
+        // Still sent so mirrors of the output switch too
+        self.write_bytes(b"\x1b[?1049h");
+
+        let cursor = self.get_cursor_pos();
+        let mut grid = ptr::null_mut();
+        let malloc = self.builder.as_ref().and_then(|b| b.malloc);
+        if let Some(malloc) = malloc
+            && !self.ctx.is_null()
+            && self.enabled
+        {
+            unsafe {
+                grid = malloc(flanterm_rs_fb_grid_size(self.ctx));
+                if !grid.is_null() {
+                    flanterm_rs_fb_save_grid(self.ctx, grid);
+                }
+            }
+            // Saving flushed pending output without reporting where it went
+            self.mark_all_dirty();
+        }
+        self.main_screen = Some(SavedScreen { grid, cursor });
+        self.clear();
+    }
+
+    /// Go back to the main screen saved by [`enter_alt_screen`](Self::enter_alt_screen)
+    ///
+    /// Does nothing if the alternate screen is not shown.
+    pub fn leave_alt_screen(&mut self) {
+        let Some(saved) = self.main_screen.take() else {
+            return;
+        };
+        self.write_bytes(b"\x1b[?1049l");
+
+        if saved.grid.is_null() {
+            self.erase_screen();
+        } else {
+            if !self.ctx.is_null() && self.enabled {
+                self.drain_write_buffer();
+                unsafe {
+                    flanterm_rs_fb_restore_grid(self.ctx, saved.grid);
+                }
+                self.mark_all_dirty();
+            }
+            self.free_saved_grid(saved.grid);
+        }
+        let (x, y) = saved.cursor;
+        let _ = write!(self, "\x1b[{};{}H", y + 1, x + 1);
+    }
+
+    /// Check whether the alternate screen is shown
+    pub fn in_alt_screen(&self) -> bool {
+        self.main_screen.is_some()
+    }
+
+    /// Drop a saved main screen without restoring it, e.g. before the grid is torn down
+    pub(crate) fn discard_main_screen(&mut self) {
+        if let Some(saved) = self.main_screen.take()
+            && !saved.grid.is_null()
+        {
+            self.free_saved_grid(saved.grid);
+        }
+    }
+
+    fn free_saved_grid(&self, grid: *mut c_void) {
+        // A grid is only ever allocated when the builder has an allocator
+        if let Some(free) = self.builder.as_ref().and_then(|b| b.free) {
+            unsafe {
+                free(grid);
+            }
+        }
+    }
+}
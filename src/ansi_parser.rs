@@ -0,0 +1,632 @@
+//! Standalone parser for terminal output
+//!
+//! [`Parser`] is independent of any flanterm context: it can inspect what is
+//! about to be written, or drive a terminal emulator of its own. It keeps
+//! its state between calls, so input can be fed in arbitrary pieces.
+
+use crate::{AnsiColor, ColorSpec, OSC_MAX_DATA};
+use core::fmt;
+use core::ops::Deref;
+
+/// Most CSI parameters kept; sequences with more are reported as [`AnsiEvent::Unknown`]
+const MAX_PARAMS: usize = 16;
+
+const REPLACEMENT: char = '\u{fffd}';
+
+/// Direction of a relative cursor movement
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Up,
+    Down,
+    Forward,
+    Back,
+}
+
+/// Payload of an OSC sequence, the bytes after the first `;`
+#[derive(Clone)]
+pub struct OscData {
+    bytes: [u8; OSC_MAX_DATA],
+    len: usize,
+}
+
+impl OscData {
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes[..self.len]
+    }
+}
+
+impl Deref for OscData {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+impl PartialEq for OscData {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_bytes() == other.as_bytes()
+    }
+}
+
+impl Eq for OscData {}
+
+impl fmt::Debug for OscData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_bytes().fmt(f)
+    }
+}
+
+/// Something the parser recognized in the input
+// There is no allocator to box the OSC payload with
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AnsiEvent {
+    /// A printable character; malformed UTF-8 comes through as U+FFFD
+    Print(char),
+    /// A C0 control character such as `\n`, `\r` or `\t`, or DEL
+    Control(u8),
+    /// CUU, CUD, CUF or CUB; `n` is at least 1
+    CursorMove { dir: Direction, n: u16 },
+    /// An SGR parameter selecting a foreground or background color
+    SetColor { color: ColorSpec, background: bool },
+    /// Any other SGR parameter, including 0 and the default color resets 39 and 49
+    SetAttribute(u16),
+    /// ED with its parameter (0 below, 1 above, 2 all, 3 saved lines)
+    EraseDisplay(u8),
+    /// EL with its parameter (0 to the end, 1 to the start, 2 whole line)
+    EraseLine(u8),
+    /// A DEC private mode being set (`CSI ? Pn h`) or reset (`CSI ? Pn l`)
+    SetMode(u16, bool),
+    /// An OSC sequence with a numeric code and at most [`OSC_MAX_DATA`] bytes of data
+    OscSequence(u32, OscData),
+    /// Any other complete escape sequence or string
+    Unknown,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum State {
+    Ground,
+    Escape,
+    EscapeIntermediate,
+    Csi,
+    Osc,
+    /// Inside a DCS, SOS, PM or APC string
+    String,
+    /// Saw ESC inside a string, a `\\` completes the ST terminator
+    StringEscape {
+        osc: bool,
+    },
+}
+
+/// Byte-at-a-time state machine turning terminal output into [`AnsiEvent`]s
+///
+/// Parameters are single numbers separated by `;`; sequences using `:`
+/// sub-parameters are reported as [`AnsiEvent::Unknown`], as are OSC
+/// sequences with a non-numeric code or more than [`OSC_MAX_DATA`] bytes
+/// of data.
+#[derive(Clone)]
+pub struct Parser {
+    state: State,
+    params: [u16; MAX_PARAMS],
+    param_count: usize,
+    private: bool,
+    /// The current CSI sequence will be reported as unknown
+    csi_invalid: bool,
+    osc_code: u32,
+    osc_in_code: bool,
+    osc_invalid: bool,
+    osc_data: OscData,
+    utf8: [u8; 4],
+    utf8_len: usize,
+    utf8_need: usize,
+}
+
+impl Default for Parser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Parser {
+    pub const fn new() -> Self {
+        Self {
+            state: State::Ground,
+            params: [0; MAX_PARAMS],
+            param_count: 0,
+            private: false,
+            csi_invalid: false,
+            osc_code: 0,
+            osc_in_code: false,
+            osc_invalid: false,
+            osc_data: OscData {
+                bytes: [0; OSC_MAX_DATA],
+                len: 0,
+            },
+            utf8: [0; 4],
+            utf8_len: 0,
+            utf8_need: 0,
+        }
+    }
+
+    /// Parse `bytes`, calling `f` for each event in order
+    pub fn feed(&mut self, bytes: &[u8], mut f: impl FnMut(AnsiEvent)) {
+        for &byte in bytes {
+            self.advance(byte, &mut f);
+        }
+    }
+
+    /// Parse a single byte, calling `f` for each event it completes
+    ///
+    /// Most bytes complete at most one event, but an SGR sequence produces
+    /// one per parameter and a DEC mode sequence one per mode.
+    pub fn advance(&mut self, byte: u8, mut f: impl FnMut(AnsiEvent)) {
+        if self.state != State::Ground && matches!(byte, 0x18 | 0x1a) {
+            // CAN and SUB abort any sequence
+            self.state = State::Ground;
+            return;
+        }
+
+        match self.state {
+            State::Ground => self.ground(byte, &mut f),
+            State::Escape => match byte {
+                b'[' => self.begin_csi(),
+                b']' => self.begin_osc(),
+                b'P' | b'X' | b'^' | b'_' => self.state = State::String,
+                0x20..=0x2f => self.state = State::EscapeIntermediate,
+                0x30..=0x7e => {
+                    self.state = State::Ground;
+                    f(AnsiEvent::Unknown);
+                }
+                _ => self.in_sequence_control(byte, &mut f),
+            },
+            State::EscapeIntermediate => match byte {
+                0x20..=0x2f => {}
+                0x30..=0x7e => {
+                    self.state = State::Ground;
+                    f(AnsiEvent::Unknown);
+                }
+                _ => self.in_sequence_control(byte, &mut f),
+            },
+            State::Csi => match byte {
+                b'0'..=b'9' => {
+                    let param = &mut self.params[self.param_count - 1];
+                    *param = param
+                        .saturating_mul(10)
+                        .saturating_add((byte - b'0') as u16);
+                }
+                b';' if self.param_count < MAX_PARAMS => self.param_count += 1,
+                b'?' if self.param_count == 1 && self.params[0] == 0 && !self.private => {
+                    self.private = true
+                }
+                0x20..=0x3f => self.csi_invalid = true,
+                0x40..=0x7e => {
+                    self.state = State::Ground;
+                    self.dispatch_csi(byte, &mut f);
+                }
+                _ => self.in_sequence_control(byte, &mut f),
+            },
+            State::Osc => match byte {
+                0x07 => self.finish_osc(&mut f),
+                0x1b => self.state = State::StringEscape { osc: true },
+                b'0'..=b'9' if self.osc_in_code => {
+                    match self
+                        .osc_code
+                        .checked_mul(10)
+                        .and_then(|code| code.checked_add((byte - b'0') as u32))
+                    {
+                        Some(code) => self.osc_code = code,
+                        None => self.osc_invalid = true,
+                    }
+                }
+                b';' if self.osc_in_code => self.osc_in_code = false,
+                _ if self.osc_in_code => self.osc_invalid = true,
+                _ if self.osc_data.len == OSC_MAX_DATA => self.osc_invalid = true,
+                _ => {
+                    self.osc_data.bytes[self.osc_data.len] = byte;
+                    self.osc_data.len += 1;
+                }
+            },
+            State::String => match byte {
+                0x07 => {
+                    self.state = State::Ground;
+                    f(AnsiEvent::Unknown);
+                }
+                0x1b => self.state = State::StringEscape { osc: false },
+                _ => {}
+            },
+            State::StringEscape { osc } => match byte {
+                b'\\' if osc => self.finish_osc(&mut f),
+                b'\\' => {
+                    self.state = State::Ground;
+                    f(AnsiEvent::Unknown);
+                }
+                // The ESC started a new sequence, abandoning the string
+                _ => {
+                    self.state = State::Escape;
+                    self.advance(byte, f);
+                }
+            },
+        }
+    }
+
+    fn ground(&mut self, byte: u8, f: &mut impl FnMut(AnsiEvent)) {
+        if self.utf8_need > 0 {
+            if byte & 0xc0 == 0x80 {
+                self.utf8[self.utf8_len] = byte;
+                self.utf8_len += 1;
+                if self.utf8_len == self.utf8_need {
+                    let ch = core::str::from_utf8(&self.utf8[..self.utf8_len])
+                        .ok()
+                        .and_then(|s| s.chars().next())
+                        .unwrap_or(REPLACEMENT);
+                    self.utf8_need = 0;
+                    f(AnsiEvent::Print(ch));
+                }
+                return;
+            }
+            self.utf8_need = 0;
+            f(AnsiEvent::Print(REPLACEMENT));
+        }
+
+        match byte {
+            0x1b => self.state = State::Escape,
+            0x00..=0x1f | 0x7f => f(AnsiEvent::Control(byte)),
+            0x20..=0x7e => f(AnsiEvent::Print(byte as char)),
+            0xc2..=0xf4 => {
+                self.utf8[0] = byte;
+                self.utf8_len = 1;
+                self.utf8_need = match byte {
+                    0xc2..=0xdf => 2,
+                    0xe0..=0xef => 3,
+                    _ => 4,
+                };
+            }
+            _ => f(AnsiEvent::Print(REPLACEMENT)),
+        }
+    }
+
+    /// Handle a byte that cannot continue an escape or control sequence
+    fn in_sequence_control(&mut self, byte: u8, f: &mut impl FnMut(AnsiEvent)) {
+        match byte {
+            // A new ESC abandons the current sequence
+            0x1b => self.state = State::Escape,
+            // C0 controls are carried out without interrupting the sequence
+            0x00..=0x1f => f(AnsiEvent::Control(byte)),
+            0x7f => {}
+            _ => {
+                self.state = State::Ground;
+                f(AnsiEvent::Unknown);
+            }
+        }
+    }
+
+    fn begin_csi(&mut self) {
+        self.state = State::Csi;
+        self.params = [0; MAX_PARAMS];
+        self.param_count = 1;
+        self.private = false;
+        self.csi_invalid = false;
+    }
+
+    fn begin_osc(&mut self) {
+        self.state = State::Osc;
+        self.osc_code = 0;
+        self.osc_in_code = true;
+        self.osc_invalid = false;
+        self.osc_data.len = 0;
+    }
+
+    fn finish_osc(&mut self, f: &mut impl FnMut(AnsiEvent)) {
+        self.state = State::Ground;
+        if self.osc_invalid {
+            f(AnsiEvent::Unknown);
+        } else {
+            f(AnsiEvent::OscSequence(self.osc_code, self.osc_data.clone()));
+        }
+    }
+
+    fn dispatch_csi(&self, final_byte: u8, f: &mut impl FnMut(AnsiEvent)) {
+        let params = &self.params[..self.param_count];
+        let first = params[0];
+        let dir = match (self.csi_invalid, self.private, final_byte) {
+            (true, _, _) => None,
+            (false, true, b'h' | b'l') => {
+                for &mode in params {
+                    f(AnsiEvent::SetMode(mode, final_byte == b'h'));
+                }
+                return;
+            }
+            (false, true, _) => None,
+            (false, false, b'A') => Some(Direction::Up),
+            (false, false, b'B') => Some(Direction::Down),
+            (false, false, b'C') => Some(Direction::Forward),
+            (false, false, b'D') => Some(Direction::Back),
+            (false, false, b'J') => return f(AnsiEvent::EraseDisplay(first.min(255) as u8)),
+            (false, false, b'K') => return f(AnsiEvent::EraseLine(first.min(255) as u8)),
+            (false, false, b'm') => return sgr(params, f),
+            (false, false, _) => None,
+        };
+        match dir {
+            Some(dir) => f(AnsiEvent::CursorMove {
+                dir,
+                n: first.max(1),
+            }),
+            None => f(AnsiEvent::Unknown),
+        }
+    }
+}
+
+/// Report each parameter of an SGR sequence
+fn sgr(params: &[u16], f: &mut impl FnMut(AnsiEvent)) {
+    let mut params = params.iter().copied();
+    while let Some(param) = params.next() {
+        let (color, background) = match param {
+            30..=37 => (ansi(param - 30), false),
+            90..=97 => (ansi(param - 90 + 8), false),
+            40..=47 => (ansi(param - 40), true),
+            100..=107 => (ansi(param - 100 + 8), true),
+            38 | 48 => {
+                let color = match params.next() {
+                    Some(5) => byte(params.next()).map(ColorSpec::Palette),
+                    Some(2) => match (
+                        byte(params.next()),
+                        byte(params.next()),
+                        byte(params.next()),
+                    ) {
+                        (Some(r), Some(g), Some(b)) => Some(ColorSpec::Rgb(r, g, b)),
+                        _ => None,
+                    },
+                    _ => None,
+                };
+                (color, param == 48)
+            }
+            _ => {
+                f(AnsiEvent::SetAttribute(param));
+                continue;
+            }
+        };
+        match color {
+            Some(color) => f(AnsiEvent::SetColor { color, background }),
+            // The rest of the sequence cannot be interpreted reliably
+            None => return f(AnsiEvent::Unknown),
+        }
+    }
+}
+
+fn ansi(index: u16) -> Option<ColorSpec> {
+    AnsiColor::from_index(index as u8).map(ColorSpec::Ansi)
+}
+
+fn byte(param: Option<u16>) -> Option<u8> {
+    param.and_then(|p| u8::try_from(p).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use std::vec::Vec;
+
+    fn parse(parser: &mut Parser, input: &[u8]) -> Vec<AnsiEvent> {
+        let mut events = Vec::new();
+        parser.feed(input, |event| events.push(event));
+        events
+    }
+
+    fn parse_all(input: &[u8]) -> Vec<AnsiEvent> {
+        parse(&mut Parser::new(), input)
+    }
+
+    fn osc(code: u32, data: &[u8]) -> AnsiEvent {
+        let mut osc_data = OscData {
+            bytes: [0; OSC_MAX_DATA],
+            len: data.len(),
+        };
+        osc_data.bytes[..data.len()].copy_from_slice(data);
+        AnsiEvent::OscSequence(code, osc_data)
+    }
+
+    #[test]
+    fn text_and_controls() {
+        assert_eq!(
+            parse_all(b"a\r\n\x7f"),
+            [
+                AnsiEvent::Print('a'),
+                AnsiEvent::Control(b'\r'),
+                AnsiEvent::Control(b'\n'),
+                AnsiEvent::Control(0x7f),
+            ]
+        );
+    }
+
+    #[test]
+    fn utf8_split_across_feeds() {
+        let mut parser = Parser::new();
+        assert_eq!(parse(&mut parser, b"\xe2\x82"), []);
+        assert_eq!(parse(&mut parser, b"\xac"), [AnsiEvent::Print('€')]);
+    }
+
+    #[test]
+    fn malformed_utf8_is_replaced() {
+        // Overlong encodings of '/'
+        assert_eq!(
+            parse_all(b"\xc0\xaf"),
+            [AnsiEvent::Print(REPLACEMENT), AnsiEvent::Print(REPLACEMENT)]
+        );
+        assert_eq!(parse_all(b"\xe0\x80\xaf"), [AnsiEvent::Print(REPLACEMENT)]);
+        // A truncated sequence followed by ASCII keeps the ASCII
+        assert_eq!(
+            parse_all(b"\xe2\x82a"),
+            [AnsiEvent::Print(REPLACEMENT), AnsiEvent::Print('a')]
+        );
+    }
+
+    #[test]
+    fn csi_split_across_feeds() {
+        let mut parser = Parser::new();
+        assert_eq!(parse(&mut parser, b"\x1b["), []);
+        assert_eq!(parse(&mut parser, b"1"), []);
+        assert_eq!(
+            parse(&mut parser, b"2A"),
+            [AnsiEvent::CursorMove {
+                dir: Direction::Up,
+                n: 12
+            }]
+        );
+    }
+
+    #[test]
+    fn cursor_moves_default_to_one() {
+        assert_eq!(
+            parse_all(b"\x1b[C\x1b[0D"),
+            [
+                AnsiEvent::CursorMove {
+                    dir: Direction::Forward,
+                    n: 1
+                },
+                AnsiEvent::CursorMove {
+                    dir: Direction::Back,
+                    n: 1
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn erase_sequences() {
+        assert_eq!(
+            parse_all(b"\x1b[J\x1b[2J\x1b[1K"),
+            [
+                AnsiEvent::EraseDisplay(0),
+                AnsiEvent::EraseDisplay(2),
+                AnsiEvent::EraseLine(1),
+            ]
+        );
+    }
+
+    #[test]
+    fn sgr_reports_each_parameter() {
+        assert_eq!(
+            parse_all(b"\x1b[0;1;31;104;38;5;200;48;2;1;2;3m"),
+            [
+                AnsiEvent::SetAttribute(0),
+                AnsiEvent::SetAttribute(1),
+                AnsiEvent::SetColor {
+                    color: ColorSpec::Ansi(AnsiColor::Red),
+                    background: false
+                },
+                AnsiEvent::SetColor {
+                    color: ColorSpec::Ansi(AnsiColor::BrightBlue),
+                    background: true
+                },
+                AnsiEvent::SetColor {
+                    color: ColorSpec::Palette(200),
+                    background: false
+                },
+                AnsiEvent::SetColor {
+                    color: ColorSpec::Rgb(1, 2, 3),
+                    background: true
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn truncated_extended_color_stops_the_sgr() {
+        assert_eq!(parse_all(b"\x1b[38;2;1;2m"), [AnsiEvent::Unknown]);
+    }
+
+    #[test]
+    fn colon_parameters_are_unknown() {
+        assert_eq!(
+            parse_all(b"\x1b[4:3mx"),
+            [AnsiEvent::Unknown, AnsiEvent::Print('x')]
+        );
+    }
+
+    #[test]
+    fn parameter_limit() {
+        // Sixteen parameters fit
+        let events = parse_all(b"\x1b[1;1;1;1;1;1;1;1;1;1;1;1;1;1;1;1m");
+        assert_eq!(events.len(), 16);
+        assert!(events.iter().all(|e| *e == AnsiEvent::SetAttribute(1)));
+        // A seventeenth makes the sequence unknown
+        assert_eq!(
+            parse_all(b"\x1b[1;1;1;1;1;1;1;1;1;1;1;1;1;1;1;1;1m"),
+            [AnsiEvent::Unknown]
+        );
+    }
+
+    #[test]
+    fn dec_modes() {
+        assert_eq!(
+            parse_all(b"\x1b[?25l\x1b[?1049;2004h"),
+            [
+                AnsiEvent::SetMode(25, false),
+                AnsiEvent::SetMode(1049, true),
+                AnsiEvent::SetMode(2004, true),
+            ]
+        );
+    }
+
+    #[test]
+    fn can_and_sub_abort_sequences() {
+        assert_eq!(
+            parse_all(b"\x1b[12\x18A\x1b]0;title\x1ab"),
+            [AnsiEvent::Print('A'), AnsiEvent::Print('b')]
+        );
+    }
+
+    #[test]
+    fn controls_inside_csi_are_carried_out() {
+        assert_eq!(
+            parse_all(b"\x1b[2\nJ"),
+            [AnsiEvent::Control(b'\n'), AnsiEvent::EraseDisplay(2)]
+        );
+    }
+
+    #[test]
+    fn esc_restarts_a_sequence() {
+        assert_eq!(parse_all(b"\x1b[3\x1b[K"), [AnsiEvent::EraseLine(0)]);
+    }
+
+    #[test]
+    fn osc_terminators() {
+        assert_eq!(
+            parse_all(b"\x1b]0;title\x07\x1b]8;;link\x1b\\"),
+            [osc(0, b"title"), osc(8, b";link")]
+        );
+    }
+
+    #[test]
+    fn osc_split_across_feeds() {
+        let mut parser = Parser::new();
+        assert_eq!(parse(&mut parser, b"\x1b]2;ab"), []);
+        assert_eq!(parse(&mut parser, b"c\x1b"), []);
+        assert_eq!(parse(&mut parser, b"\\"), [osc(2, b"abc")]);
+    }
+
+    #[test]
+    fn invalid_osc_is_unknown() {
+        assert_eq!(parse_all(b"\x1b]x;data\x07"), [AnsiEvent::Unknown]);
+        assert_eq!(
+            parse_all(b"\x1b]99999999999;data\x07"),
+            [AnsiEvent::Unknown]
+        );
+
+        let mut long = Vec::from(&b"\x1b]0;"[..]);
+        long.resize(long.len() + OSC_MAX_DATA + 1, b'a');
+        long.push(0x07);
+        assert_eq!(parse_all(&long), [AnsiEvent::Unknown]);
+    }
+
+    #[test]
+    fn dcs_and_escapes_are_unknown() {
+        assert_eq!(
+            parse_all(b"\x1bPq#0\x1b\\\x1b(B\x1bc"),
+            [AnsiEvent::Unknown, AnsiEvent::Unknown, AnsiEvent::Unknown]
+        );
+    }
+}
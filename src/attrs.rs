@@ -0,0 +1,465 @@
+use crate::{ATTR_STACK_DEPTH, ColorSpec, FlantermContext};
+use core::fmt::{self, Write};
+
+/// A set of SGR attributes that can be applied in one escape sequence
+///
+/// flanterm only renders bold (as bright foreground), blink (as bright
+/// background), reverse and colors; `dim`, `italic`, `underline`,
+/// `double_underline`, `rapid_blink`, `hidden`, `strikethrough` and
+/// `overline` are still sent so the output stays correct on other
+/// terminals, but flanterm ignores them. At most one of `underline` and
+/// `double_underline` is tracked as set, as they are styles of the same
+/// attribute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TextAttributes {
+    pub bold: bool,
+    pub dim: bool,
+    pub italic: bool,
+    pub underline: bool,
+    pub double_underline: bool,
+    pub blink: bool,
+    pub rapid_blink: bool,
+    pub reverse: bool,
+    pub hidden: bool,
+    pub strikethrough: bool,
+    pub overline: bool,
+    pub fg: Option<ColorSpec>,
+    pub bg: Option<ColorSpec>,
+}
+
+impl TextAttributes {
+    /// Switch on every attribute set here and select its colors
+    ///
+    /// Attributes that are off and colors that are `None` are left as they
+    /// are; write [`reset_format`](FlantermContext::reset_format) first to
+    /// start from a clean state. Nothing is written if nothing is set.
+    pub fn apply(&self, ctx: &mut FlantermContext) {
+        let mut sgr = SgrBuffer::new();
+        self.write_params(&mut sgr);
+        sgr.write_to(ctx);
+        ctx.track_attrs(|a| a.merge(self));
+    }
+
+    /// Add the SGR parameters switching on these attributes to `sgr`
+    pub(crate) fn write_params(&self, sgr: &mut SgrBuffer) {
+        // SGR 21 goes first: terminals that read it as "bold off" then still
+        // see the bold that follows
+        let flags = [
+            (self.double_underline, 21),
+            (self.bold, 1),
+            (self.dim, 2),
+            (self.italic, 3),
+            (self.underline, 4),
+            (self.blink, 5),
+            (self.rapid_blink, 6),
+            (self.reverse, 7),
+            (self.hidden, 8),
+            (self.strikethrough, 9),
+            (self.overline, 53),
+        ];
+        for (_, code) in flags.iter().filter(|(on, _)| *on) {
+            sgr.param(format_args!("{code}"));
+        }
+        if let Some(fg) = self.fg {
+            sgr.color(fg, false);
+        }
+        if let Some(bg) = self.bg {
+            sgr.color(bg, true);
+        }
+    }
+
+    /// Record `style` as the underline in effect
+    ///
+    /// `Curly` is tracked as single underline, which is what gets sent for it.
+    fn set_underline_style(&mut self, style: UnderlineStyle) {
+        self.underline = matches!(style, UnderlineStyle::Single | UnderlineStyle::Curly);
+        self.double_underline = style == UnderlineStyle::Double;
+    }
+
+    /// Combine with `other`, taking every attribute it switches on and every color it sets
+    fn merge(&mut self, other: &Self) {
+        self.bold |= other.bold;
+        self.dim |= other.dim;
+        self.italic |= other.italic;
+        if other.underline || other.double_underline {
+            self.underline = other.underline;
+            self.double_underline = other.double_underline;
+        }
+        self.blink |= other.blink;
+        self.rapid_blink |= other.rapid_blink;
+        self.reverse |= other.reverse;
+        self.hidden |= other.hidden;
+        self.strikethrough |= other.strikethrough;
+        self.overline |= other.overline;
+        self.fg = other.fg.or(self.fg);
+        self.bg = other.bg.or(self.bg);
+    }
+}
+
+/// Underline variants selectable through [`FlantermContext::set_underline_style`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UnderlineStyle {
+    #[default]
+    None,
+    Single,
+    Double,
+    Curly,
+}
+
+impl FlantermContext {
+    /// Apply a [`TextAttributes`] set in one escape sequence
+    pub fn apply_attrs(&mut self, attrs: &TextAttributes) {
+        attrs.apply(self);
+    }
+
+    /// Get the attributes set through this wrapper, or `None` if none have been set yet
+    ///
+    /// Escape sequences written directly with [`write_bytes`](Self::write_bytes)
+    /// or `write!` are not parsed, so the result only reflects the helper
+    /// methods of this crate.
+    pub fn current_attrs(&self) -> Option<TextAttributes> {
+        self.attrs
+    }
+
+    /// Save the current attributes so [`pop_attrs`](Self::pop_attrs) can restore them
+    ///
+    /// Up to [`ATTR_STACK_DEPTH`] snapshots are kept; pushing onto a full
+    /// stack does nothing, so the matching pop restores an outer snapshot.
+    /// If no attributes are known yet, a snapshot of the unknown state is
+    /// pushed and restoring it just resets formatting.
+    pub fn push_attrs(&mut self) {
+        if self.attr_depth == ATTR_STACK_DEPTH {
+            return;
+        }
+        self.attr_stack[self.attr_depth] = self.attrs;
+        self.attr_depth += 1;
+    }
+
+    /// Restore the attributes saved by the last [`push_attrs`](Self::push_attrs)
+    ///
+    /// Writes `\x1b[0m` followed by the saved attributes. Does nothing if
+    /// the stack is empty.
+    pub fn pop_attrs(&mut self) {
+        if self.attr_depth == 0 {
+            return;
+        }
+        self.attr_depth -= 1;
+        let saved = self.attr_stack[self.attr_depth].take();
+        self.reset_format();
+        if let Some(attrs) = saved {
+            attrs.apply(self);
+        }
+    }
+
+    /// Record an attribute change made through one of the helpers
+    pub(crate) fn track_attrs(&mut self, update: impl FnOnce(&mut TextAttributes)) {
+        update(self.attrs.get_or_insert_with(TextAttributes::default));
+    }
+
+    /// Switch on bold, which flanterm renders as the bright foreground color
+    pub fn set_bold(&mut self) {
+        self.write_bytes(b"\x1b[1m");
+        self.track_attrs(|a| a.bold = true);
+    }
+
+    /// Switch off bold (and dim)
+    pub fn unset_bold(&mut self) {
+        self.write_bytes(b"\x1b[22m");
+        self.track_attrs(|a| {
+            a.bold = false;
+            a.dim = false;
+        });
+    }
+
+    /// Switch on dim (ignored by flanterm)
+    pub fn set_dim(&mut self) {
+        self.write_bytes(b"\x1b[2m");
+        self.track_attrs(|a| a.dim = true);
+    }
+
+    /// Switch off dim (and bold)
+    pub fn unset_dim(&mut self) {
+        self.write_bytes(b"\x1b[22m");
+        self.track_attrs(|a| {
+            a.bold = false;
+            a.dim = false;
+        });
+    }
+
+    /// Switch on italic (ignored by flanterm)
+    pub fn set_italic(&mut self) {
+        self.write_bytes(b"\x1b[3m");
+        self.track_attrs(|a| a.italic = true);
+    }
+
+    /// Switch off italic
+    pub fn unset_italic(&mut self) {
+        self.write_bytes(b"\x1b[23m");
+        self.track_attrs(|a| a.italic = false);
+    }
+
+    /// Switch on underline (ignored by flanterm)
+    pub fn set_underline(&mut self) {
+        self.write_bytes(b"\x1b[4m");
+        self.track_attrs(|a| a.set_underline_style(UnderlineStyle::Single));
+    }
+
+    /// Switch off underline
+    pub fn unset_underline(&mut self) {
+        self.write_bytes(b"\x1b[24m");
+        self.track_attrs(|a| a.set_underline_style(UnderlineStyle::None));
+    }
+
+    /// Switch on blink, which flanterm renders as the bright background color
+    pub fn set_blink(&mut self) {
+        self.write_bytes(b"\x1b[5m");
+        self.track_attrs(|a| a.blink = true);
+    }
+
+    /// Switch off blink (both slow and rapid)
+    pub fn unset_blink(&mut self) {
+        self.write_bytes(b"\x1b[25m");
+        self.track_attrs(|a| {
+            a.blink = false;
+            a.rapid_blink = false;
+        });
+    }
+
+    /// Switch on rapid blink (ignored by flanterm)
+    pub fn set_rapid_blink(&mut self) {
+        self.write_bytes(b"\x1b[6m");
+        self.track_attrs(|a| a.rapid_blink = true);
+    }
+
+    /// Switch off rapid blink (and slow blink)
+    pub fn unset_rapid_blink(&mut self) {
+        self.write_bytes(b"\x1b[25m");
+        self.track_attrs(|a| {
+            a.blink = false;
+            a.rapid_blink = false;
+        });
+    }
+
+    /// Swap foreground and background colors
+    pub fn set_reverse(&mut self) {
+        self.write_bytes(b"\x1b[7m");
+        self.track_attrs(|a| a.reverse = true);
+    }
+
+    /// Stop swapping foreground and background colors
+    pub fn unset_reverse(&mut self) {
+        self.write_bytes(b"\x1b[27m");
+        self.track_attrs(|a| a.reverse = false);
+    }
+
+    /// Switch on hidden text (ignored by flanterm)
+    pub fn set_hidden(&mut self) {
+        self.write_bytes(b"\x1b[8m");
+        self.track_attrs(|a| a.hidden = true);
+    }
+
+    /// Switch off hidden text
+    pub fn unset_hidden(&mut self) {
+        self.write_bytes(b"\x1b[28m");
+        self.track_attrs(|a| a.hidden = false);
+    }
+
+    /// Switch on strikethrough (ignored by flanterm)
+    pub fn set_strikethrough(&mut self) {
+        self.write_bytes(b"\x1b[9m");
+        self.track_attrs(|a| a.strikethrough = true);
+    }
+
+    /// Switch off strikethrough
+    pub fn unset_strikethrough(&mut self) {
+        self.write_bytes(b"\x1b[29m");
+        self.track_attrs(|a| a.strikethrough = false);
+    }
+
+    /// Switch overline on (SGR 53) or off (SGR 55); ignored by flanterm
+    pub fn set_overline(&mut self, on: bool) {
+        self.write_bytes(if on { b"\x1b[53m" } else { b"\x1b[55m" });
+        self.track_attrs(|a| a.overline = on);
+    }
+
+    /// Switch on double underline (SGR 21)
+    ///
+    /// Some terminals, notably the Linux console and older xterm, treat SGR
+    /// 21 as "bold off" instead. flanterm does neither: it ignores the code,
+    /// leaving bold as it was. Use [`unset_bold`](Self::unset_bold) to clear
+    /// bold portably.
+    pub fn set_double_underline(&mut self) {
+        self.write_bytes(b"\x1b[21m");
+        self.track_attrs(|a| a.set_underline_style(UnderlineStyle::Double));
+    }
+
+    /// Select an underline style, or switch underlining off with `UnderlineStyle::None`
+    ///
+    /// `Curly` would normally be sent as the `4:3` sub-parameter, but
+    /// flanterm treats `:` as the end of the sequence and prints the rest as
+    /// text, so it is sent as plain single underline instead. flanterm does
+    /// not draw underlines of any style.
+    pub fn set_underline_style(&mut self, style: UnderlineStyle) {
+        self.write_bytes(match style {
+            UnderlineStyle::None => b"\x1b[24m",
+            UnderlineStyle::Single | UnderlineStyle::Curly => b"\x1b[4m",
+            UnderlineStyle::Double => b"\x1b[21m",
+        });
+        self.track_attrs(|a| a.set_underline_style(style));
+    }
+}
+
+/// Most parameters flanterm keeps from one escape sequence; it drops the rest
+const MAX_SGR_PARAMS: usize = 16;
+
+/// Fixed-capacity buffer collecting SGR parameters into `\x1b[...m` sequences
+///
+/// A parameter group that would take the current sequence past
+/// [`MAX_SGR_PARAMS`] starts a new sequence instead, so long attribute sets
+/// are sent as two sequences rather than losing their last color.
+pub(crate) struct SgrBuffer {
+    buf: [u8; 96],
+    len: usize,
+    /// Parameters in the sequence being collected
+    params: usize,
+}
+
+impl SgrBuffer {
+    pub(crate) fn new() -> Self {
+        let mut buf = [0; 96];
+        buf[..2].copy_from_slice(b"\x1b[");
+        Self {
+            buf,
+            len: 2,
+            params: 0,
+        }
+    }
+
+    /// Append one parameter, separated from the previous one by `;`
+    pub(crate) fn param(&mut self, param: fmt::Arguments<'_>) {
+        self.begin_group(1);
+        let _ = self.write_fmt(param);
+    }
+
+    pub(crate) fn color(&mut self, color: ColorSpec, background: bool) {
+        self.begin_group(color.param_count());
+        let _ = color.write_params(self, background);
+    }
+
+    /// Make room for a group of `count` parameters that has to stay in one sequence
+    fn begin_group(&mut self, count: usize) {
+        if self.params + count > MAX_SGR_PARAMS {
+            let _ = self.write_str("m\x1b[");
+            self.params = 0;
+        } else if self.params > 0 {
+            let _ = self.write_str(";");
+        }
+        self.params += count;
+    }
+
+    /// Terminate the sequence and write it, unless no parameter was added
+    pub(crate) fn write_to(mut self, ctx: &mut FlantermContext) {
+        if self.params == 0 {
+            return;
+        }
+        let _ = self.write_str("m");
+        ctx.write_bytes(&self.buf[..self.len]);
+    }
+}
+
+impl Write for SgrBuffer {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let end = self.len + s.len();
+        if end > self.buf.len() {
+            return Err(fmt::Error);
+        }
+        self.buf[self.len..end].copy_from_slice(s.as_bytes());
+        self.len = end;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(attrs: &TextAttributes, reset: bool) -> SgrBuffer {
+        let mut sgr = SgrBuffer::new();
+        if reset {
+            sgr.param(format_args!("0"));
+        }
+        attrs.write_params(&mut sgr);
+        let _ = sgr.write_str("m");
+        sgr
+    }
+
+    #[test]
+    fn short_set_is_one_sequence() {
+        let attrs = TextAttributes {
+            bold: true,
+            fg: Some(ColorSpec::Palette(208)),
+            ..TextAttributes::default()
+        };
+        let sgr = params(&attrs, true);
+        assert_eq!(&sgr.buf[..sgr.len], b"\x1b[0;1;38;5;208m");
+    }
+
+    #[test]
+    fn long_set_is_split_between_colors() {
+        let attrs = TextAttributes {
+            bold: true,
+            dim: true,
+            italic: true,
+            underline: true,
+            blink: true,
+            rapid_blink: true,
+            reverse: true,
+            hidden: true,
+            strikethrough: true,
+            overline: true,
+            fg: Some(ColorSpec::Rgb(255, 255, 255)),
+            bg: Some(ColorSpec::Rgb(255, 255, 255)),
+            ..TextAttributes::default()
+        };
+        // The reset, ten flags and the foreground fill the first sequence
+        let sgr = params(&attrs, true);
+        assert_eq!(
+            &sgr.buf[..sgr.len],
+            b"\x1b[0;1;2;3;4;5;6;7;8;9;53;38;2;255;255;255m\x1b[48;2;255;255;255m"
+        );
+    }
+
+    #[test]
+    fn color_is_not_split() {
+        // 14 parameters, so the next five-parameter color starts a new sequence
+        let mut sgr = SgrBuffer::new();
+        for _ in 0..14 {
+            sgr.param(format_args!("1"));
+        }
+        sgr.color(ColorSpec::Rgb(1, 2, 3), false);
+        sgr.color(ColorSpec::Ansi(crate::AnsiColor::Red), true);
+        let _ = sgr.write_str("m");
+        assert_eq!(
+            &sgr.buf[..sgr.len],
+            b"\x1b[1;1;1;1;1;1;1;1;1;1;1;1;1;1m\x1b[38;2;1;2;3;41m"
+        );
+    }
+
+    #[test]
+    fn double_underline_replaces_underline() {
+        let mut attrs = TextAttributes {
+            underline: true,
+            ..TextAttributes::default()
+        };
+        attrs.merge(&TextAttributes {
+            double_underline: true,
+            ..TextAttributes::default()
+        });
+        assert!(attrs.double_underline && !attrs.underline);
+
+        attrs.set_underline_style(UnderlineStyle::Curly);
+        assert!(attrs.underline && !attrs.double_underline);
+        attrs.set_underline_style(UnderlineStyle::None);
+        assert!(!attrs.underline && !attrs.double_underline);
+    }
+}
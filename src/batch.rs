@@ -0,0 +1,50 @@
+use crate::attrs::SgrBuffer;
+use crate::{FlantermContext, TextAttributes};
+
+/// A run of text with the attributes to draw it in, for [`FlantermContext::write_batch`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BatchSegment<'a> {
+    pub text: &'a str,
+    /// Attributes for this segment, or `None` for plain text
+    pub attrs: Option<TextAttributes>,
+}
+
+impl<'a> BatchSegment<'a> {
+    pub const fn new(text: &'a str, attrs: Option<TextAttributes>) -> Self {
+        Self { text, attrs }
+    }
+
+    /// A segment drawn in the default attributes
+    pub const fn plain(text: &'a str) -> Self {
+        Self::new(text, None)
+    }
+}
+
+impl FlantermContext {
+    /// Write each segment in its own attributes, e.g. for a colorized log line
+    ///
+    /// Every segment starts from reset formatting, and formatting is reset
+    /// once more at the end. The reset after one segment and the attributes
+    /// of the next are sent as a single escape sequence, and no sequence is
+    /// sent between consecutive plain segments.
+    pub fn write_batch(&mut self, segments: &[BatchSegment<'_>]) {
+        // The attributes in effect before the batch are unknown
+        let mut styled = true;
+        for segment in segments {
+            if styled || segment.attrs.is_some() {
+                let mut sgr = SgrBuffer::new();
+                sgr.param(format_args!("0"));
+                if let Some(attrs) = &segment.attrs {
+                    attrs.write_params(&mut sgr);
+                }
+                sgr.write_to(self);
+                styled = segment.attrs.is_some();
+            }
+            self.write_bytes(segment.text.as_bytes());
+        }
+        if styled {
+            self.write_bytes(b"\x1b[0m");
+        }
+        self.attrs = Some(TextAttributes::default());
+    }
+}
@@ -120,6 +120,7 @@ pub const FLANTERM_OOB_OUTPUT_ONLRET: u32 = 32;
 pub const FLANTERM_OOB_OUTPUT_ONOCR: u32 = 64;
 pub const FLANTERM_OOB_OUTPUT_OPOST: u32 = 128;
 pub const FLANTERM_FB_H: u32 = 1;
+pub const FLANTERM_RS_H: u32 = 1;
 pub type wchar_t = ::core::ffi::c_int;
 #[repr(C)]
 #[repr(align(16))]
@@ -308,3 +309,88 @@ unsafe extern "C" {
         margin: usize,
     ) -> *mut flanterm_context;
 }
+unsafe extern "C" {
+    pub fn flanterm_rs_fb_set_ansi_colour(ctx: *mut flanterm_context, index: usize, colour: u32);
+}
+unsafe extern "C" {
+    pub fn flanterm_rs_fb_set_default_colours(
+        ctx: *mut flanterm_context,
+        default_bg: *const u32,
+        default_fg: *const u32,
+        default_bg_bright: *const u32,
+        default_fg_bright: *const u32,
+    );
+}
+unsafe extern "C" {
+    pub fn flanterm_rs_get_cursor_pos(ctx: *mut flanterm_context, x: *mut usize, y: *mut usize);
+}
+unsafe extern "C" {
+    pub fn flanterm_rs_set_tab_size(ctx: *mut flanterm_context, tab_size: usize);
+}
+unsafe extern "C" {
+    pub fn flanterm_rs_get_tab_size(ctx: *mut flanterm_context) -> usize;
+}
+unsafe extern "C" {
+    pub fn flanterm_rs_delete_lines(ctx: *mut flanterm_context, count: usize);
+}
+unsafe extern "C" {
+    pub fn flanterm_rs_scroll(ctx: *mut flanterm_context, count: usize, down: bool);
+}
+unsafe extern "C" {
+    pub fn flanterm_rs_fb_grid_size(ctx: *mut flanterm_context) -> usize;
+}
+unsafe extern "C" {
+    pub fn flanterm_rs_fb_save_grid(ctx: *mut flanterm_context, dest: *mut ::core::ffi::c_void);
+}
+unsafe extern "C" {
+    pub fn flanterm_rs_fb_restore_grid(ctx: *mut flanterm_context, src: *const ::core::ffi::c_void);
+}
+unsafe extern "C" {
+    pub fn flanterm_rs_fb_get_framebuffer(
+        ctx: *mut flanterm_context,
+        framebuffer: *mut *mut u32,
+        width: *mut usize,
+        height: *mut usize,
+        pitch: *mut usize,
+    );
+}
+unsafe extern "C" {
+    pub fn flanterm_rs_get_autoflush(ctx: *mut flanterm_context) -> bool;
+}
+unsafe extern "C" {
+    pub fn flanterm_rs_fb_set_framebuffer(ctx: *mut flanterm_context, framebuffer: *mut u32);
+}
+unsafe extern "C" {
+    pub fn flanterm_rs_fb_flush_bounds(
+        ctx: *mut flanterm_context,
+        x: *mut usize,
+        y: *mut usize,
+        width: *mut usize,
+        height: *mut usize,
+        cells: *mut u8,
+        cells_len: usize,
+    ) -> bool;
+}
+unsafe extern "C" {
+    pub fn flanterm_rs_fb_get_cell_geometry(
+        ctx: *mut flanterm_context,
+        offset_x: *mut usize,
+        offset_y: *mut usize,
+        glyph_width: *mut usize,
+        glyph_height: *mut usize,
+    );
+}
+unsafe extern "C" {
+    pub fn flanterm_rs_fb_refresh_rows(ctx: *mut flanterm_context, start: usize, count: usize);
+}
+unsafe extern "C" {
+    pub fn flanterm_rs_fb_blank_grid(ctx: *mut flanterm_context, colour: *mut u32) -> bool;
+}
+unsafe extern "C" {
+    pub fn flanterm_rs_fb_set_glyph_shadow(
+        ctx: *mut flanterm_context,
+        dx: i32,
+        dy: i32,
+        colour: u32,
+    ) -> bool;
+}
@@ -0,0 +1,65 @@
+use crate::{FlantermContext, PixelFormat};
+
+impl FlantermContext {
+    /// Create a context on the highest-resolution framebuffer limine reported
+    ///
+    /// Returns `None` if there is no framebuffer, the chosen one is not a
+    /// 32-bit direct-color framebuffer, or flanterm fails to initialize on it.
+    #[cfg(feature = "limine")]
+    pub fn from_limine_fb(fb: &limine::response::FramebufferResponse) -> Option<Self> {
+        let fb = fb
+            .framebuffers()
+            .max_by_key(|fb| fb.width() * fb.height())?;
+        if fb.memory_model() != limine::framebuffer::MemoryModel::RGB || fb.bpp() != 32 {
+            return None;
+        }
+
+        let format = PixelFormat::from_masks(
+            fb.red_mask_size(),
+            fb.red_mask_shift(),
+            fb.green_mask_size(),
+            fb.green_mask_shift(),
+            fb.blue_mask_size(),
+            fb.blue_mask_shift(),
+        );
+        Self::new_fb_simple(
+            fb.addr() as *mut u32,
+            fb.width() as usize,
+            fb.height() as usize,
+            fb.pitch() as usize,
+            format,
+        )
+    }
+
+    /// Create a context on the framebuffer described by a multiboot2 tag
+    ///
+    /// The tag holds a physical address, so the framebuffer must be identity
+    /// mapped when this is called. Returns `None` for indexed-color and EGA
+    /// text framebuffers, anything other than 32 bits per pixel, or if
+    /// flanterm fails to initialize.
+    #[cfg(feature = "multiboot2")]
+    pub fn from_multiboot2_fb(tag: &multiboot2::FramebufferTag) -> Option<Self> {
+        let multiboot2::FramebufferType::RGB { red, green, blue } = tag.buffer_type().ok()? else {
+            return None;
+        };
+        if tag.bpp() != 32 {
+            return None;
+        }
+
+        let format = PixelFormat::from_masks(
+            red.size,
+            red.position,
+            green.size,
+            green.position,
+            blue.size,
+            blue.position,
+        );
+        Self::new_fb_simple(
+            tag.address() as usize as *mut u32,
+            tag.width() as usize,
+            tag.height() as usize,
+            tag.pitch() as usize,
+            format,
+        )
+    }
+}
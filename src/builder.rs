@@ -0,0 +1,340 @@
+use crate::bindings::*;
+use crate::fonts::PsfFont;
+use crate::{Color, ColorScheme, FbInitError, FlantermConfig, FlantermContext, PixelFormat};
+use core::ffi::c_void;
+use core::ptr;
+
+/// Allocation callback handed to flanterm
+pub type MallocFn = unsafe extern "C" fn(usize) -> *mut c_void;
+
+/// Deallocation callback handed to flanterm
+pub type FreeFn = unsafe extern "C" fn(*mut c_void);
+
+/// Builder for a framebuffer-based flanterm context
+///
+/// Only the framebuffer geometry is required; every other `flanterm_fb_init`
+/// parameter starts out at the same default `FlantermContext::new_fb` uses.
+#[derive(Debug, Clone)]
+pub struct FlantermContextBuilder {
+    pub(crate) config: FlantermConfig,
+    pub(crate) malloc: Option<MallocFn>,
+    pub(crate) free: Option<FreeFn>,
+    pub(crate) canvas: *const Color,
+    /// Offset and 0x00RRGGBB color of the shadow drawn beneath glyphs
+    pub(crate) glyph_shadow: Option<(i32, i32, u32)>,
+    pub(crate) on_init: Option<fn(&mut FlantermContext)>,
+    pub(crate) on_deinit: Option<fn(&mut FlantermContext)>,
+}
+
+impl FlantermContextBuilder {
+    /// Start building a context for the given framebuffer
+    ///
+    /// `pitch` is the length of one scanline in bytes. The color masks
+    /// default to 8 bits per channel with red at bit 16, green at bit 8 and
+    /// blue at bit 0.
+    pub fn new(framebuffer: *mut u32, width: usize, height: usize, pitch: usize) -> Self {
+        Self::from_config(&FlantermConfig::new(framebuffer, width, height, pitch))
+    }
+
+    /// Start building a context from a configuration snapshot
+    pub fn from_config(config: &FlantermConfig) -> Self {
+        Self {
+            config: *config,
+            malloc: None,
+            free: None,
+            canvas: ptr::null(),
+            glyph_shadow: None,
+            on_init: None,
+            on_deinit: None,
+        }
+    }
+
+    /// Have flanterm allocate its buffers through `malloc` and `free`
+    ///
+    /// Without an allocator flanterm falls back to a static bump allocator,
+    /// which only supports a single context per boot, caps the usable area at
+    /// 1920x1200 and disables canvas and sixel support. flanterm keeps the
+    /// most recently supplied allocator in global state, so every context
+    /// should use the same pair.
+    ///
+    /// The C library treats a null `malloc` with a non-null `free` (or vice
+    /// versa) as undefined behavior; taking both here rules that out.
+    pub fn allocator(&mut self, malloc: MallocFn, free: FreeFn) -> &mut Self {
+        self.malloc = Some(malloc);
+        self.free = Some(free);
+        self
+    }
+
+    /// Set the size and shift of each color channel mask
+    pub fn color_masks(
+        &mut self,
+        red_mask_size: u8,
+        red_mask_shift: u8,
+        green_mask_size: u8,
+        green_mask_shift: u8,
+        blue_mask_size: u8,
+        blue_mask_shift: u8,
+    ) -> &mut Self {
+        self.pixel_format(PixelFormat::from_masks(
+            red_mask_size,
+            red_mask_shift,
+            green_mask_size,
+            green_mask_shift,
+            blue_mask_size,
+            blue_mask_shift,
+        ))
+    }
+
+    /// Set the channel layout from a [`PixelFormat`]
+    pub fn pixel_format(&mut self, format: PixelFormat) -> &mut Self {
+        self.config.pixel_format = format;
+        self
+    }
+
+    /// Use a custom bitmap font of `width`x`height` pixel glyphs
+    ///
+    /// The font must contain 256 glyphs with one byte per row, so `width`
+    /// must be 8; wider cells are made with [`font_spacing`](Self::font_spacing).
+    /// Passing a null pointer selects the built-in 8x16 font.
+    pub fn font(&mut self, font: *const u8, width: usize, height: usize) -> &mut Self {
+        self.config.font = font as usize as u64;
+        self.config.font_width = width;
+        self.config.font_height = height;
+        self
+    }
+
+    /// Use a font loaded with [`fonts::load_psf1`](crate::fonts::load_psf1) or
+    /// [`fonts::load_psf2`](crate::fonts::load_psf2)
+    ///
+    /// Glyphs narrower than 8 pixels are drawn in 8-pixel cells. The first
+    /// 256 glyphs are used, by CP437 code. Fonts flanterm cannot draw with
+    /// (see [`PsfFont::is_flanterm_compatible`]) make
+    /// [`build`](Self::build) fail with [`FbInitError::InvalidFont`].
+    pub fn psf_font(&mut self, font: &PsfFont) -> &mut Self {
+        // A width of 0 is what validation rejects as an invalid font
+        let width = if font.is_flanterm_compatible() { 8 } else { 0 };
+        self.font(font.glyph_data().as_ptr(), width, font.height())
+    }
+
+    /// Set the number of blank pixel columns between glyphs
+    pub fn font_spacing(&mut self, spacing: usize) -> &mut Self {
+        self.config.font_spacing = spacing;
+        self
+    }
+
+    /// Set the glyph scale factors (0, 0 lets flanterm pick based on resolution)
+    pub fn font_scale(&mut self, scale_x: usize, scale_y: usize) -> &mut Self {
+        self.config.font_scale_x = scale_x;
+        self.config.font_scale_y = scale_y;
+        self
+    }
+
+    /// Draw a shadow beneath every glyph, offset by (`dx`, `dy`) pixels
+    ///
+    /// The shadow is a copy of the glyph in the 0x00RRGGBB `color`, which
+    /// keeps text readable over a busy [`canvas`](Self::canvas). It is
+    /// clipped to the glyph's own cell, so offsets should stay within the
+    /// blank pixels the font leaves around its glyphs, such as the column
+    /// added by [`font_spacing`](Self::font_spacing). At most 16 contexts
+    /// can have a shadow at a time; [`build`](Self::build) fails with
+    /// [`FbInitError::FlantermAllocFailed`] beyond that.
+    pub fn glyph_shadow(&mut self, dx: i32, dy: i32, color: u32) -> &mut Self {
+        self.glyph_shadow = Some((dx, dy, color));
+        self
+    }
+
+    /// Call `hook` on the new context once [`build`](Self::build) has created it
+    ///
+    /// Not called again when the context is [reinitialized](FlantermContext::reinit_fb).
+    pub fn on_init(&mut self, hook: fn(&mut FlantermContext)) -> &mut Self {
+        self.on_init = Some(hook);
+        self
+    }
+
+    /// Call `hook` on the context when it is dropped, while it can still draw
+    pub fn on_deinit(&mut self, hook: fn(&mut FlantermContext)) -> &mut Self {
+        self.on_deinit = Some(hook);
+        self
+    }
+
+    /// Set the margin around the text area in pixels
+    pub fn margin(&mut self, margin: usize) -> &mut Self {
+        self.config.margin = margin;
+        self
+    }
+
+    /// Set the 8 normal ANSI colors
+    pub fn ansi_colors(&mut self, colors: &[Color; 8]) -> &mut Self {
+        self.config.ansi_colors = Some(*colors);
+        self
+    }
+
+    /// Set the full 16-color ANSI palette
+    ///
+    /// `normal` covers SGR 30-37/40-47 and `bright` covers 90-97/100-107 (and
+    /// the bold variants of the normal colors).
+    pub fn ansi_palette(&mut self, normal: &[Color; 8], bright: &[Color; 8]) -> &mut Self {
+        self.config.ansi_colors = Some(*normal);
+        self.config.ansi_bright_colors = Some(*bright);
+        self
+    }
+
+    /// Set the default foreground color
+    ///
+    /// The default colors are what `\x1b[0m`, `\x1b[39m` and `\x1b[49m`
+    /// reset to, so custom defaults survive attribute resets.
+    pub fn default_fg(&mut self, color: Color) -> &mut Self {
+        self.config.default_fg = Some(color);
+        self
+    }
+
+    /// Set the default background color
+    pub fn default_bg(&mut self, color: Color) -> &mut Self {
+        self.config.default_bg = Some(color);
+        self
+    }
+
+    /// Set the default foreground color used while bold is active
+    pub fn default_fg_bright(&mut self, color: Color) -> &mut Self {
+        self.config.default_fg_bright = Some(color);
+        self
+    }
+
+    /// Set the default background color used while blink (bright background) is active
+    pub fn default_bg_bright(&mut self, color: Color) -> &mut Self {
+        self.config.default_bg_bright = Some(color);
+        self
+    }
+
+    /// Set the ANSI palette and all four default colors from a [`ColorScheme`]
+    pub fn color_scheme(&mut self, scheme: &ColorScheme) -> &mut Self {
+        self.ansi_palette(&scheme.ansi, &scheme.ansi_bright)
+            .default_fg(scheme.fg)
+            .default_bg(scheme.bg)
+            .default_fg_bright(scheme.fg_bright)
+            .default_bg_bright(scheme.bg_bright)
+    }
+
+    /// Set a background image drawn behind the terminal text
+    ///
+    /// `pixels` holds `width * height` colors in row-major order
+    /// with no per-row padding, i.e. at the framebuffer's resolution rather
+    /// than its pitch. Cells using the default background color show the
+    /// canvas, with glyphs drawn over it in their foreground color.
+    ///
+    /// flanterm copies the image while building, but the pointer is kept in
+    /// the context's configuration and read again whenever the context is
+    /// rebuilt from it, so the buffer must stay live for the lifetime of the
+    /// context. The canvas is ignored unless an [`allocator`](Self::allocator)
+    /// is set, because the static bump allocator has no room for it.
+    pub fn canvas(&mut self, pixels: *const Color) -> &mut Self {
+        self.canvas = pixels;
+        self
+    }
+
+    /// Create the context
+    pub fn build(&self) -> Result<FlantermContext, FbInitError> {
+        self.validate()?;
+
+        let ctx = self.init_raw();
+        if ctx.is_null() {
+            Err(FbInitError::FlantermAllocFailed)
+        } else {
+            let mut ctx = FlantermContext::from_parts(ctx, Some(self.clone()));
+            if let Some(hook) = self.on_init {
+                hook(&mut ctx);
+            }
+            Ok(ctx)
+        }
+    }
+
+    /// Catch the parameter errors flanterm would otherwise fail on (or misrender) silently
+    pub(crate) fn validate(&self) -> Result<(), FbInitError> {
+        let cfg = &self.config;
+        if cfg.framebuffer == 0 {
+            return Err(FbInitError::NullFramebuffer);
+        }
+        if cfg.width == 0 {
+            return Err(FbInitError::ZeroWidth);
+        }
+        if cfg.height == 0 {
+            return Err(FbInitError::ZeroHeight);
+        }
+        // A width too large for its scanline to be addressed fits no pitch
+        match cfg.width.checked_mul(4) {
+            Some(min_required) if cfg.pitch >= min_required => {}
+            min_required => {
+                return Err(FbInitError::InvalidPitch {
+                    pitch: cfg.pitch,
+                    min_required: min_required.unwrap_or(usize::MAX),
+                });
+            }
+        }
+        let (red_size, _, green_size, _, blue_size, _) = cfg.pixel_format.masks();
+        if red_size < 8 || red_size != green_size || red_size != blue_size {
+            return Err(FbInitError::UnsupportedPixelFormat);
+        }
+        if cfg.font != 0 && (cfg.font_width != 8 || cfg.font_height == 0) {
+            return Err(FbInitError::InvalidFont);
+        }
+        Ok(())
+    }
+
+    pub(crate) fn init_raw(&self) -> *mut flanterm_context {
+        let cfg = &self.config;
+        let (rs, rsh, gs, gsh, bs, bsh) = cfg.pixel_format.masks();
+        let ctx = unsafe {
+            flanterm_fb_init(
+                self.malloc,
+                self.free,
+                cfg.framebuffer_ptr(),
+                cfg.width,
+                cfg.height,
+                cfg.pitch,
+                rs,
+                rsh,
+                gs,
+                gsh,
+                bs,
+                bsh,
+                self.canvas as *mut u32,
+                colors_ptr(&cfg.ansi_colors),
+                colors_ptr(&cfg.ansi_bright_colors),
+                color_ptr(&cfg.default_bg),
+                color_ptr(&cfg.default_fg),
+                color_ptr(&cfg.default_bg_bright),
+                color_ptr(&cfg.default_fg_bright),
+                cfg.font_ptr() as *mut c_void,
+                cfg.font_width,
+                cfg.font_height,
+                cfg.font_spacing,
+                cfg.font_scale_x,
+                cfg.font_scale_y,
+                cfg.margin,
+            )
+        };
+        if let Some((dx, dy, color)) = self.glyph_shadow
+            && !ctx.is_null()
+            && !unsafe { flanterm_rs_fb_set_glyph_shadow(ctx, dx, dy, color) }
+        {
+            unsafe { flanterm_deinit(ctx) };
+            return ptr::null_mut();
+        }
+        ctx
+    }
+}
+
+/// flanterm only reads through color pointers, so handing out `*mut` is fine
+///
+/// `Color` is a transparent `u32`, so arrays of it can be handed over as is.
+fn colors_ptr<const N: usize>(colors: &Option<[Color; N]>) -> *mut u32 {
+    colors
+        .as_ref()
+        .map_or(ptr::null_mut(), |c| c.as_ptr() as *mut u32)
+}
+
+fn color_ptr(color: &Option<Color>) -> *mut u32 {
+    color
+        .as_ref()
+        .map_or(ptr::null_mut(), |c| &c.0 as *const u32 as *mut u32)
+}
@@ -0,0 +1,46 @@
+use crate::FlantermContext;
+
+/// Terminal features an application may want to check for before using them
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TerminalCapability {
+    /// 24-bit color through `38;2;R;G;B` and `48;2;R;G;B`
+    TrueColor,
+    /// The 256-color palette through `38;5;N` and `48;5;N`
+    Color256,
+    /// A bold typeface for SGR 1
+    BoldFont,
+    /// Underlined text for SGR 4
+    Underline,
+    /// Blinking text for SGR 5
+    Blink,
+    /// The alternate screen through [`enter_alt_screen`](FlantermContext::enter_alt_screen)
+    AltScreen,
+    BracketedPaste,
+    MouseTracking,
+    /// Sixel graphics in DCS strings
+    Sixel,
+}
+
+impl FlantermContext {
+    /// Check whether this context can provide `cap`
+    ///
+    /// flanterm renders bold as the bright variant of the foreground color
+    /// and blink as a bright background, draws no underline, and as an
+    /// output-only terminal has no paste or mouse reporting. The alternate
+    /// screen and sixel graphics need memory from the
+    /// [`allocator`](crate::FlantermContextBuilder::allocator), so they are
+    /// only reported for contexts built with one.
+    pub fn supports(&self, cap: TerminalCapability) -> bool {
+        match cap {
+            TerminalCapability::TrueColor | TerminalCapability::Color256 => true,
+            TerminalCapability::BoldFont
+            | TerminalCapability::Underline
+            | TerminalCapability::Blink
+            | TerminalCapability::BracketedPaste
+            | TerminalCapability::MouseTracking => false,
+            TerminalCapability::AltScreen | TerminalCapability::Sixel => {
+                self.builder.as_ref().is_some_and(|b| b.malloc.is_some())
+            }
+        }
+    }
+}
@@ -0,0 +1,205 @@
+use crate::FlantermContext;
+use core::fmt;
+
+/// One of the 16 standard ANSI colors
+///
+/// The discriminant is the color's index in the 256-color palette, so the
+/// first eight are the normal colors and the `Bright` variants are 8-15.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum AnsiColor {
+    Black = 0,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+    BrightBlack,
+    BrightRed,
+    BrightGreen,
+    BrightYellow,
+    BrightBlue,
+    BrightMagenta,
+    BrightCyan,
+    BrightWhite,
+}
+
+impl AnsiColor {
+    /// Check whether this is one of the bright variants
+    pub const fn is_bright(self) -> bool {
+        self as u8 >= 8
+    }
+
+    /// The color with the given index (0-15)
+    pub(crate) const fn from_index(index: u8) -> Option<Self> {
+        const ALL: [AnsiColor; 16] = [
+            AnsiColor::Black,
+            AnsiColor::Red,
+            AnsiColor::Green,
+            AnsiColor::Yellow,
+            AnsiColor::Blue,
+            AnsiColor::Magenta,
+            AnsiColor::Cyan,
+            AnsiColor::White,
+            AnsiColor::BrightBlack,
+            AnsiColor::BrightRed,
+            AnsiColor::BrightGreen,
+            AnsiColor::BrightYellow,
+            AnsiColor::BrightBlue,
+            AnsiColor::BrightMagenta,
+            AnsiColor::BrightCyan,
+            AnsiColor::BrightWhite,
+        ];
+        if (index as usize) < ALL.len() {
+            Some(ALL[index as usize])
+        } else {
+            None
+        }
+    }
+
+    /// SGR parameter selecting this color as the foreground (30-37 or 90-97)
+    pub(crate) const fn fg_code(self) -> u8 {
+        if self.is_bright() {
+            90 + (self as u8 - 8)
+        } else {
+            30 + self as u8
+        }
+    }
+
+    /// SGR parameter selecting this color as the background (40-47 or 100-107)
+    pub(crate) const fn bg_code(self) -> u8 {
+        self.fg_code() + 10
+    }
+}
+
+impl From<AnsiColor> for u8 {
+    fn from(color: AnsiColor) -> Self {
+        color as u8
+    }
+}
+
+/// A 24-bit color stored as 0x00RRGGBB, the layout flanterm uses for palettes and canvases
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[repr(transparent)]
+pub struct Color(pub u32);
+
+impl Color {
+    /// Pack separate channels into a color
+    pub const fn from_rgb(r: u8, g: u8, b: u8) -> Self {
+        Self((r as u32) << 16 | (g as u32) << 8 | b as u32)
+    }
+
+    /// Red channel
+    pub const fn r(self) -> u8 {
+        (self.0 >> 16) as u8
+    }
+
+    /// Green channel
+    pub const fn g(self) -> u8 {
+        (self.0 >> 8) as u8
+    }
+
+    /// Blue channel
+    pub const fn b(self) -> u8 {
+        self.0 as u8
+    }
+}
+
+impl From<u32> for Color {
+    fn from(value: u32) -> Self {
+        Self(value)
+    }
+}
+
+impl From<Color> for u32 {
+    fn from(color: Color) -> Self {
+        color.0
+    }
+}
+
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{:02X}{:02X}{:02X}", self.r(), self.g(), self.b())
+    }
+}
+
+/// Any of the ways SGR can select a text color
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorSpec {
+    /// One of the 16 standard colors (SGR 30-37/90-97 and 40-47/100-107)
+    Ansi(AnsiColor),
+    /// An entry of the 256-color palette (`38;5;N`)
+    Palette(u8),
+    /// A 24-bit color (`38;2;R;G;B`)
+    Rgb(u8, u8, u8),
+}
+
+impl ColorSpec {
+    /// Write the SGR parameters selecting this color as foreground or background
+    pub(crate) fn write_params(self, out: &mut impl fmt::Write, background: bool) -> fmt::Result {
+        let base = if background { 48 } else { 38 };
+        match self {
+            Self::Ansi(color) if background => write!(out, "{}", color.bg_code()),
+            Self::Ansi(color) => write!(out, "{}", color.fg_code()),
+            Self::Palette(index) => write!(out, "{base};5;{index}"),
+            Self::Rgb(r, g, b) => write!(out, "{base};2;{r};{g};{b}"),
+        }
+    }
+
+    /// Number of SGR parameters [`write_params`](Self::write_params) writes
+    pub(crate) fn param_count(self) -> usize {
+        match self {
+            Self::Ansi(_) => 1,
+            Self::Palette(_) => 3,
+            Self::Rgb(..) => 5,
+        }
+    }
+}
+
+/// A foreground and background pair of 256-color palette indices
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ColorPair {
+    pub fg: u8,
+    pub bg: u8,
+}
+
+impl ColorPair {
+    /// The terminal's default foreground and background
+    ///
+    /// Applying this sends `\x1b[39;49m` like
+    /// [`reset_colors`](crate::FlantermContext::reset_colors), so it follows
+    /// custom default colors. Its indices are 7 and 0, light gray on black,
+    /// which is what flanterm's stock defaults look like; [`invert`](Self::invert)
+    /// and the fields work with those.
+    pub const DEFAULT: Self = Self::new(7, 0);
+
+    pub const fn new(fg: u8, bg: u8) -> Self {
+        Self { fg, bg }
+    }
+
+    /// Select both colors
+    ///
+    /// A pair equal to [`DEFAULT`](Self::DEFAULT), including
+    /// `ColorPair::new(7, 0)`, selects the default colors rather than the
+    /// palette entries.
+    pub fn apply(&self, ctx: &mut FlantermContext) {
+        if *self == Self::DEFAULT {
+            ctx.reset_colors();
+        } else {
+            ctx.set_color(self.fg, Some(self.bg));
+        }
+    }
+
+    /// The same pair with foreground and background swapped
+    pub const fn invert(&self) -> Self {
+        Self::new(self.bg, self.fg)
+    }
+}
+
+impl From<(u8, u8)> for ColorPair {
+    fn from((fg, bg): (u8, u8)) -> Self {
+        Self::new(fg, bg)
+    }
+}
@@ -0,0 +1,314 @@
+//! Named color constants
+
+use crate::Color;
+
+pub const BLACK: Color = Color(0x000000);
+pub const RED: Color = Color(0xff0000);
+pub const GREEN: Color = Color(0x00ff00);
+pub const YELLOW: Color = Color(0xffff00);
+pub const BLUE: Color = Color(0x0000ff);
+pub const MAGENTA: Color = Color(0xff00ff);
+pub const CYAN: Color = Color(0x00ffff);
+pub const WHITE: Color = Color(0xffffff);
+pub const GRAY: Color = Color(0x808080);
+
+/// The xterm 256-color palette
+///
+/// Entries 0-15 are xterm's stock ANSI colors; flanterm draws those from
+/// its own configurable palette instead. Entries 16-255 (the 6x6x6 cube and
+/// the grayscale ramp) match what flanterm renders for `38;5;N`.
+pub mod xterm {
+    use crate::Color;
+
+    pub const COLOR_0: Color = Color(0x000000);
+    pub const COLOR_1: Color = Color(0xcd0000);
+    pub const COLOR_2: Color = Color(0x00cd00);
+    pub const COLOR_3: Color = Color(0xcdcd00);
+    pub const COLOR_4: Color = Color(0x0000ee);
+    pub const COLOR_5: Color = Color(0xcd00cd);
+    pub const COLOR_6: Color = Color(0x00cdcd);
+    pub const COLOR_7: Color = Color(0xe5e5e5);
+    pub const COLOR_8: Color = Color(0x7f7f7f);
+    pub const COLOR_9: Color = Color(0xff0000);
+    pub const COLOR_10: Color = Color(0x00ff00);
+    pub const COLOR_11: Color = Color(0xffff00);
+    pub const COLOR_12: Color = Color(0x5c5cff);
+    pub const COLOR_13: Color = Color(0xff00ff);
+    pub const COLOR_14: Color = Color(0x00ffff);
+    pub const COLOR_15: Color = Color(0xffffff);
+    pub const COLOR_16: Color = Color(0x000000);
+    pub const COLOR_17: Color = Color(0x00005f);
+    pub const COLOR_18: Color = Color(0x000087);
+    pub const COLOR_19: Color = Color(0x0000af);
+    pub const COLOR_20: Color = Color(0x0000d7);
+    pub const COLOR_21: Color = Color(0x0000ff);
+    pub const COLOR_22: Color = Color(0x005f00);
+    pub const COLOR_23: Color = Color(0x005f5f);
+    pub const COLOR_24: Color = Color(0x005f87);
+    pub const COLOR_25: Color = Color(0x005faf);
+    pub const COLOR_26: Color = Color(0x005fd7);
+    pub const COLOR_27: Color = Color(0x005fff);
+    pub const COLOR_28: Color = Color(0x008700);
+    pub const COLOR_29: Color = Color(0x00875f);
+    pub const COLOR_30: Color = Color(0x008787);
+    pub const COLOR_31: Color = Color(0x0087af);
+    pub const COLOR_32: Color = Color(0x0087d7);
+    pub const COLOR_33: Color = Color(0x0087ff);
+    pub const COLOR_34: Color = Color(0x00af00);
+    pub const COLOR_35: Color = Color(0x00af5f);
+    pub const COLOR_36: Color = Color(0x00af87);
+    pub const COLOR_37: Color = Color(0x00afaf);
+    pub const COLOR_38: Color = Color(0x00afd7);
+    pub const COLOR_39: Color = Color(0x00afff);
+    pub const COLOR_40: Color = Color(0x00d700);
+    pub const COLOR_41: Color = Color(0x00d75f);
+    pub const COLOR_42: Color = Color(0x00d787);
+    pub const COLOR_43: Color = Color(0x00d7af);
+    pub const COLOR_44: Color = Color(0x00d7d7);
+    pub const COLOR_45: Color = Color(0x00d7ff);
+    pub const COLOR_46: Color = Color(0x00ff00);
+    pub const COLOR_47: Color = Color(0x00ff5f);
+    pub const COLOR_48: Color = Color(0x00ff87);
+    pub const COLOR_49: Color = Color(0x00ffaf);
+    pub const COLOR_50: Color = Color(0x00ffd7);
+    pub const COLOR_51: Color = Color(0x00ffff);
+    pub const COLOR_52: Color = Color(0x5f0000);
+    pub const COLOR_53: Color = Color(0x5f005f);
+    pub const COLOR_54: Color = Color(0x5f0087);
+    pub const COLOR_55: Color = Color(0x5f00af);
+    pub const COLOR_56: Color = Color(0x5f00d7);
+    pub const COLOR_57: Color = Color(0x5f00ff);
+    pub const COLOR_58: Color = Color(0x5f5f00);
+    pub const COLOR_59: Color = Color(0x5f5f5f);
+    pub const COLOR_60: Color = Color(0x5f5f87);
+    pub const COLOR_61: Color = Color(0x5f5faf);
+    pub const COLOR_62: Color = Color(0x5f5fd7);
+    pub const COLOR_63: Color = Color(0x5f5fff);
+    pub const COLOR_64: Color = Color(0x5f8700);
+    pub const COLOR_65: Color = Color(0x5f875f);
+    pub const COLOR_66: Color = Color(0x5f8787);
+    pub const COLOR_67: Color = Color(0x5f87af);
+    pub const COLOR_68: Color = Color(0x5f87d7);
+    pub const COLOR_69: Color = Color(0x5f87ff);
+    pub const COLOR_70: Color = Color(0x5faf00);
+    pub const COLOR_71: Color = Color(0x5faf5f);
+    pub const COLOR_72: Color = Color(0x5faf87);
+    pub const COLOR_73: Color = Color(0x5fafaf);
+    pub const COLOR_74: Color = Color(0x5fafd7);
+    pub const COLOR_75: Color = Color(0x5fafff);
+    pub const COLOR_76: Color = Color(0x5fd700);
+    pub const COLOR_77: Color = Color(0x5fd75f);
+    pub const COLOR_78: Color = Color(0x5fd787);
+    pub const COLOR_79: Color = Color(0x5fd7af);
+    pub const COLOR_80: Color = Color(0x5fd7d7);
+    pub const COLOR_81: Color = Color(0x5fd7ff);
+    pub const COLOR_82: Color = Color(0x5fff00);
+    pub const COLOR_83: Color = Color(0x5fff5f);
+    pub const COLOR_84: Color = Color(0x5fff87);
+    pub const COLOR_85: Color = Color(0x5fffaf);
+    pub const COLOR_86: Color = Color(0x5fffd7);
+    pub const COLOR_87: Color = Color(0x5fffff);
+    pub const COLOR_88: Color = Color(0x870000);
+    pub const COLOR_89: Color = Color(0x87005f);
+    pub const COLOR_90: Color = Color(0x870087);
+    pub const COLOR_91: Color = Color(0x8700af);
+    pub const COLOR_92: Color = Color(0x8700d7);
+    pub const COLOR_93: Color = Color(0x8700ff);
+    pub const COLOR_94: Color = Color(0x875f00);
+    pub const COLOR_95: Color = Color(0x875f5f);
+    pub const COLOR_96: Color = Color(0x875f87);
+    pub const COLOR_97: Color = Color(0x875faf);
+    pub const COLOR_98: Color = Color(0x875fd7);
+    pub const COLOR_99: Color = Color(0x875fff);
+    pub const COLOR_100: Color = Color(0x878700);
+    pub const COLOR_101: Color = Color(0x87875f);
+    pub const COLOR_102: Color = Color(0x878787);
+    pub const COLOR_103: Color = Color(0x8787af);
+    pub const COLOR_104: Color = Color(0x8787d7);
+    pub const COLOR_105: Color = Color(0x8787ff);
+    pub const COLOR_106: Color = Color(0x87af00);
+    pub const COLOR_107: Color = Color(0x87af5f);
+    pub const COLOR_108: Color = Color(0x87af87);
+    pub const COLOR_109: Color = Color(0x87afaf);
+    pub const COLOR_110: Color = Color(0x87afd7);
+    pub const COLOR_111: Color = Color(0x87afff);
+    pub const COLOR_112: Color = Color(0x87d700);
+    pub const COLOR_113: Color = Color(0x87d75f);
+    pub const COLOR_114: Color = Color(0x87d787);
+    pub const COLOR_115: Color = Color(0x87d7af);
+    pub const COLOR_116: Color = Color(0x87d7d7);
+    pub const COLOR_117: Color = Color(0x87d7ff);
+    pub const COLOR_118: Color = Color(0x87ff00);
+    pub const COLOR_119: Color = Color(0x87ff5f);
+    pub const COLOR_120: Color = Color(0x87ff87);
+    pub const COLOR_121: Color = Color(0x87ffaf);
+    pub const COLOR_122: Color = Color(0x87ffd7);
+    pub const COLOR_123: Color = Color(0x87ffff);
+    pub const COLOR_124: Color = Color(0xaf0000);
+    pub const COLOR_125: Color = Color(0xaf005f);
+    pub const COLOR_126: Color = Color(0xaf0087);
+    pub const COLOR_127: Color = Color(0xaf00af);
+    pub const COLOR_128: Color = Color(0xaf00d7);
+    pub const COLOR_129: Color = Color(0xaf00ff);
+    pub const COLOR_130: Color = Color(0xaf5f00);
+    pub const COLOR_131: Color = Color(0xaf5f5f);
+    pub const COLOR_132: Color = Color(0xaf5f87);
+    pub const COLOR_133: Color = Color(0xaf5faf);
+    pub const COLOR_134: Color = Color(0xaf5fd7);
+    pub const COLOR_135: Color = Color(0xaf5fff);
+    pub const COLOR_136: Color = Color(0xaf8700);
+    pub const COLOR_137: Color = Color(0xaf875f);
+    pub const COLOR_138: Color = Color(0xaf8787);
+    pub const COLOR_139: Color = Color(0xaf87af);
+    pub const COLOR_140: Color = Color(0xaf87d7);
+    pub const COLOR_141: Color = Color(0xaf87ff);
+    pub const COLOR_142: Color = Color(0xafaf00);
+    pub const COLOR_143: Color = Color(0xafaf5f);
+    pub const COLOR_144: Color = Color(0xafaf87);
+    pub const COLOR_145: Color = Color(0xafafaf);
+    pub const COLOR_146: Color = Color(0xafafd7);
+    pub const COLOR_147: Color = Color(0xafafff);
+    pub const COLOR_148: Color = Color(0xafd700);
+    pub const COLOR_149: Color = Color(0xafd75f);
+    pub const COLOR_150: Color = Color(0xafd787);
+    pub const COLOR_151: Color = Color(0xafd7af);
+    pub const COLOR_152: Color = Color(0xafd7d7);
+    pub const COLOR_153: Color = Color(0xafd7ff);
+    pub const COLOR_154: Color = Color(0xafff00);
+    pub const COLOR_155: Color = Color(0xafff5f);
+    pub const COLOR_156: Color = Color(0xafff87);
+    pub const COLOR_157: Color = Color(0xafffaf);
+    pub const COLOR_158: Color = Color(0xafffd7);
+    pub const COLOR_159: Color = Color(0xafffff);
+    pub const COLOR_160: Color = Color(0xd70000);
+    pub const COLOR_161: Color = Color(0xd7005f);
+    pub const COLOR_162: Color = Color(0xd70087);
+    pub const COLOR_163: Color = Color(0xd700af);
+    pub const COLOR_164: Color = Color(0xd700d7);
+    pub const COLOR_165: Color = Color(0xd700ff);
+    pub const COLOR_166: Color = Color(0xd75f00);
+    pub const COLOR_167: Color = Color(0xd75f5f);
+    pub const COLOR_168: Color = Color(0xd75f87);
+    pub const COLOR_169: Color = Color(0xd75faf);
+    pub const COLOR_170: Color = Color(0xd75fd7);
+    pub const COLOR_171: Color = Color(0xd75fff);
+    pub const COLOR_172: Color = Color(0xd78700);
+    pub const COLOR_173: Color = Color(0xd7875f);
+    pub const COLOR_174: Color = Color(0xd78787);
+    pub const COLOR_175: Color = Color(0xd787af);
+    pub const COLOR_176: Color = Color(0xd787d7);
+    pub const COLOR_177: Color = Color(0xd787ff);
+    pub const COLOR_178: Color = Color(0xd7af00);
+    pub const COLOR_179: Color = Color(0xd7af5f);
+    pub const COLOR_180: Color = Color(0xd7af87);
+    pub const COLOR_181: Color = Color(0xd7afaf);
+    pub const COLOR_182: Color = Color(0xd7afd7);
+    pub const COLOR_183: Color = Color(0xd7afff);
+    pub const COLOR_184: Color = Color(0xd7d700);
+    pub const COLOR_185: Color = Color(0xd7d75f);
+    pub const COLOR_186: Color = Color(0xd7d787);
+    pub const COLOR_187: Color = Color(0xd7d7af);
+    pub const COLOR_188: Color = Color(0xd7d7d7);
+    pub const COLOR_189: Color = Color(0xd7d7ff);
+    pub const COLOR_190: Color = Color(0xd7ff00);
+    pub const COLOR_191: Color = Color(0xd7ff5f);
+    pub const COLOR_192: Color = Color(0xd7ff87);
+    pub const COLOR_193: Color = Color(0xd7ffaf);
+    pub const COLOR_194: Color = Color(0xd7ffd7);
+    pub const COLOR_195: Color = Color(0xd7ffff);
+    pub const COLOR_196: Color = Color(0xff0000);
+    pub const COLOR_197: Color = Color(0xff005f);
+    pub const COLOR_198: Color = Color(0xff0087);
+    pub const COLOR_199: Color = Color(0xff00af);
+    pub const COLOR_200: Color = Color(0xff00d7);
+    pub const COLOR_201: Color = Color(0xff00ff);
+    pub const COLOR_202: Color = Color(0xff5f00);
+    pub const COLOR_203: Color = Color(0xff5f5f);
+    pub const COLOR_204: Color = Color(0xff5f87);
+    pub const COLOR_205: Color = Color(0xff5faf);
+    pub const COLOR_206: Color = Color(0xff5fd7);
+    pub const COLOR_207: Color = Color(0xff5fff);
+    pub const COLOR_208: Color = Color(0xff8700);
+    pub const COLOR_209: Color = Color(0xff875f);
+    pub const COLOR_210: Color = Color(0xff8787);
+    pub const COLOR_211: Color = Color(0xff87af);
+    pub const COLOR_212: Color = Color(0xff87d7);
+    pub const COLOR_213: Color = Color(0xff87ff);
+    pub const COLOR_214: Color = Color(0xffaf00);
+    pub const COLOR_215: Color = Color(0xffaf5f);
+    pub const COLOR_216: Color = Color(0xffaf87);
+    pub const COLOR_217: Color = Color(0xffafaf);
+    pub const COLOR_218: Color = Color(0xffafd7);
+    pub const COLOR_219: Color = Color(0xffafff);
+    pub const COLOR_220: Color = Color(0xffd700);
+    pub const COLOR_221: Color = Color(0xffd75f);
+    pub const COLOR_222: Color = Color(0xffd787);
+    pub const COLOR_223: Color = Color(0xffd7af);
+    pub const COLOR_224: Color = Color(0xffd7d7);
+    pub const COLOR_225: Color = Color(0xffd7ff);
+    pub const COLOR_226: Color = Color(0xffff00);
+    pub const COLOR_227: Color = Color(0xffff5f);
+    pub const COLOR_228: Color = Color(0xffff87);
+    pub const COLOR_229: Color = Color(0xffffaf);
+    pub const COLOR_230: Color = Color(0xffffd7);
+    pub const COLOR_231: Color = Color(0xffffff);
+    pub const COLOR_232: Color = Color(0x080808);
+    pub const COLOR_233: Color = Color(0x121212);
+    pub const COLOR_234: Color = Color(0x1c1c1c);
+    pub const COLOR_235: Color = Color(0x262626);
+    pub const COLOR_236: Color = Color(0x303030);
+    pub const COLOR_237: Color = Color(0x3a3a3a);
+    pub const COLOR_238: Color = Color(0x444444);
+    pub const COLOR_239: Color = Color(0x4e4e4e);
+    pub const COLOR_240: Color = Color(0x585858);
+    pub const COLOR_241: Color = Color(0x626262);
+    pub const COLOR_242: Color = Color(0x6c6c6c);
+    pub const COLOR_243: Color = Color(0x767676);
+    pub const COLOR_244: Color = Color(0x808080);
+    pub const COLOR_245: Color = Color(0x8a8a8a);
+    pub const COLOR_246: Color = Color(0x949494);
+    pub const COLOR_247: Color = Color(0x9e9e9e);
+    pub const COLOR_248: Color = Color(0xa8a8a8);
+    pub const COLOR_249: Color = Color(0xb2b2b2);
+    pub const COLOR_250: Color = Color(0xbcbcbc);
+    pub const COLOR_251: Color = Color(0xc6c6c6);
+    pub const COLOR_252: Color = Color(0xd0d0d0);
+    pub const COLOR_253: Color = Color(0xdadada);
+    pub const COLOR_254: Color = Color(0xe4e4e4);
+    pub const COLOR_255: Color = Color(0xeeeeee);
+
+    /// All 256 entries indexed by palette number
+    pub const PALETTE: [Color; 256] = [
+        COLOR_0, COLOR_1, COLOR_2, COLOR_3, COLOR_4, COLOR_5, COLOR_6, COLOR_7, COLOR_8, COLOR_9,
+        COLOR_10, COLOR_11, COLOR_12, COLOR_13, COLOR_14, COLOR_15, COLOR_16, COLOR_17, COLOR_18,
+        COLOR_19, COLOR_20, COLOR_21, COLOR_22, COLOR_23, COLOR_24, COLOR_25, COLOR_26, COLOR_27,
+        COLOR_28, COLOR_29, COLOR_30, COLOR_31, COLOR_32, COLOR_33, COLOR_34, COLOR_35, COLOR_36,
+        COLOR_37, COLOR_38, COLOR_39, COLOR_40, COLOR_41, COLOR_42, COLOR_43, COLOR_44, COLOR_45,
+        COLOR_46, COLOR_47, COLOR_48, COLOR_49, COLOR_50, COLOR_51, COLOR_52, COLOR_53, COLOR_54,
+        COLOR_55, COLOR_56, COLOR_57, COLOR_58, COLOR_59, COLOR_60, COLOR_61, COLOR_62, COLOR_63,
+        COLOR_64, COLOR_65, COLOR_66, COLOR_67, COLOR_68, COLOR_69, COLOR_70, COLOR_71, COLOR_72,
+        COLOR_73, COLOR_74, COLOR_75, COLOR_76, COLOR_77, COLOR_78, COLOR_79, COLOR_80, COLOR_81,
+        COLOR_82, COLOR_83, COLOR_84, COLOR_85, COLOR_86, COLOR_87, COLOR_88, COLOR_89, COLOR_90,
+        COLOR_91, COLOR_92, COLOR_93, COLOR_94, COLOR_95, COLOR_96, COLOR_97, COLOR_98, COLOR_99,
+        COLOR_100, COLOR_101, COLOR_102, COLOR_103, COLOR_104, COLOR_105, COLOR_106, COLOR_107,
+        COLOR_108, COLOR_109, COLOR_110, COLOR_111, COLOR_112, COLOR_113, COLOR_114, COLOR_115,
+        COLOR_116, COLOR_117, COLOR_118, COLOR_119, COLOR_120, COLOR_121, COLOR_122, COLOR_123,
+        COLOR_124, COLOR_125, COLOR_126, COLOR_127, COLOR_128, COLOR_129, COLOR_130, COLOR_131,
+        COLOR_132, COLOR_133, COLOR_134, COLOR_135, COLOR_136, COLOR_137, COLOR_138, COLOR_139,
+        COLOR_140, COLOR_141, COLOR_142, COLOR_143, COLOR_144, COLOR_145, COLOR_146, COLOR_147,
+        COLOR_148, COLOR_149, COLOR_150, COLOR_151, COLOR_152, COLOR_153, COLOR_154, COLOR_155,
+        COLOR_156, COLOR_157, COLOR_158, COLOR_159, COLOR_160, COLOR_161, COLOR_162, COLOR_163,
+        COLOR_164, COLOR_165, COLOR_166, COLOR_167, COLOR_168, COLOR_169, COLOR_170, COLOR_171,
+        COLOR_172, COLOR_173, COLOR_174, COLOR_175, COLOR_176, COLOR_177, COLOR_178, COLOR_179,
+        COLOR_180, COLOR_181, COLOR_182, COLOR_183, COLOR_184, COLOR_185, COLOR_186, COLOR_187,
+        COLOR_188, COLOR_189, COLOR_190, COLOR_191, COLOR_192, COLOR_193, COLOR_194, COLOR_195,
+        COLOR_196, COLOR_197, COLOR_198, COLOR_199, COLOR_200, COLOR_201, COLOR_202, COLOR_203,
+        COLOR_204, COLOR_205, COLOR_206, COLOR_207, COLOR_208, COLOR_209, COLOR_210, COLOR_211,
+        COLOR_212, COLOR_213, COLOR_214, COLOR_215, COLOR_216, COLOR_217, COLOR_218, COLOR_219,
+        COLOR_220, COLOR_221, COLOR_222, COLOR_223, COLOR_224, COLOR_225, COLOR_226, COLOR_227,
+        COLOR_228, COLOR_229, COLOR_230, COLOR_231, COLOR_232, COLOR_233, COLOR_234, COLOR_235,
+        COLOR_236, COLOR_237, COLOR_238, COLOR_239, COLOR_240, COLOR_241, COLOR_242, COLOR_243,
+        COLOR_244, COLOR_245, COLOR_246, COLOR_247, COLOR_248, COLOR_249, COLOR_250, COLOR_251,
+        COLOR_252, COLOR_253, COLOR_254, COLOR_255,
+    ];
+}
@@ -0,0 +1,69 @@
+use crate::{Color, PixelFormat};
+
+/// Plain-data snapshot of every construction-time parameter of a framebuffer context
+///
+/// Pointers are stored as addresses so the snapshot can be copied freely,
+/// e.g. to hand boot-time terminal settings from a bootloader to a kernel.
+/// Allocator callbacks and the canvas are not part of the snapshot; set them
+/// on a [`FlantermContextBuilder`](crate::FlantermContextBuilder) created
+/// with [`from_config`](crate::FlantermContextBuilder::from_config).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FlantermConfig {
+    /// Address of the framebuffer
+    pub framebuffer: u64,
+    pub width: usize,
+    pub height: usize,
+    /// Length of one scanline in bytes
+    pub pitch: usize,
+    pub pixel_format: PixelFormat,
+    /// Address of the bitmap font, or 0 for the built-in font
+    pub font: u64,
+    pub font_width: usize,
+    pub font_height: usize,
+    pub font_spacing: usize,
+    pub font_scale_x: usize,
+    pub font_scale_y: usize,
+    pub margin: usize,
+    /// Normal ANSI colors, or `None` for flanterm's defaults
+    pub ansi_colors: Option<[Color; 8]>,
+    /// Bright ANSI colors, or `None` for flanterm's defaults
+    pub ansi_bright_colors: Option<[Color; 8]>,
+    pub default_fg: Option<Color>,
+    pub default_bg: Option<Color>,
+    pub default_fg_bright: Option<Color>,
+    pub default_bg_bright: Option<Color>,
+}
+
+impl FlantermConfig {
+    /// Configuration for the given framebuffer with every other parameter at its default
+    pub fn new(framebuffer: *mut u32, width: usize, height: usize, pitch: usize) -> Self {
+        Self {
+            framebuffer: framebuffer as usize as u64,
+            width,
+            height,
+            pitch,
+            pixel_format: PixelFormat::Rgb888,
+            font: 0,
+            font_width: 0,
+            font_height: 0,
+            font_spacing: 1,
+            font_scale_x: 1,
+            font_scale_y: 1,
+            margin: 0,
+            ansi_colors: None,
+            ansi_bright_colors: None,
+            default_fg: None,
+            default_bg: None,
+            default_fg_bright: None,
+            default_bg_bright: None,
+        }
+    }
+
+    pub(crate) fn framebuffer_ptr(&self) -> *mut u32 {
+        self.framebuffer as usize as *mut u32
+    }
+
+    pub(crate) fn font_ptr(&self) -> *const u8 {
+        self.font as usize as *const u8
+    }
+}
@@ -0,0 +1,295 @@
+use crate::bindings::*;
+use crate::{CursorOutOfBoundsError, FlantermContext};
+use core::fmt::Write;
+
+impl FlantermContext {
+    /// Get the cursor position as 0-indexed (column, row)
+    ///
+    /// Returns (0, 0) if the context is invalid.
+    pub fn get_cursor_pos(&self) -> (usize, usize) {
+        let mut x = 0;
+        let mut y = 0;
+        if self.ctx.is_null() {
+            return (x, y);
+        }
+        self.drain_write_buffer();
+        unsafe {
+            flanterm_rs_get_cursor_pos(self.ctx, &mut x, &mut y);
+        }
+        (x, y)
+    }
+
+    /// Move the cursor to the top left corner
+    ///
+    /// Same as `move_cursor(0, 0)`, so in [origin mode](Self::set_origin_mode)
+    /// this is the top of the scroll region.
+    pub fn home(&mut self) {
+        self.move_cursor(0, 0);
+    }
+
+    /// Move the cursor to (x, y), clamping coordinates past the edge
+    ///
+    /// Same as [`move_cursor`](Self::move_cursor), for call sites where the
+    /// clamping should be explicit.
+    pub fn move_cursor_clamped(&mut self, x: usize, y: usize) {
+        self.move_cursor(x, y);
+    }
+
+    /// Move the cursor to (x, y), or fail without moving if it is off screen
+    ///
+    /// In [origin mode](Self::set_origin_mode) rows past the bottom of the
+    /// scroll region are rejected, and the error reports the region height.
+    pub fn try_move_cursor(&mut self, x: usize, y: usize) -> Result<(), CursorOutOfBoundsError> {
+        let cols = self.get_dimensions().0;
+        let rows = self.addressable_rows().1;
+        if x >= cols || y >= rows {
+            return Err(CursorOutOfBoundsError { x, y, cols, rows });
+        }
+        self.move_cursor(x, y);
+        Ok(())
+    }
+
+    /// Make [`move_cursor`](Self::move_cursor) address rows relative to the scroll region (DECOM)
+    ///
+    /// flanterm does not implement origin mode, so the wrapper applies it
+    /// itself: `move_cursor` and [`try_move_cursor`](Self::try_move_cursor)
+    /// offset rows by the top of the region set with
+    /// [`set_scroll_region`](Self::set_scroll_region). Raw `\x1b[H`
+    /// sequences stay absolute. `\x1b[?6h`/`\x1b[?6l` is still written so
+    /// mirrors of the output see the mode change. Like DECOM, switching the
+    /// mode moves the cursor home.
+    pub fn set_origin_mode(&mut self, relative: bool) {
+        self.write_bytes(if relative { b"\x1b[?6h" } else { b"\x1b[?6l" });
+        self.origin_mode = relative;
+        self.move_cursor(0, 0);
+    }
+
+    /// Check whether origin mode is active
+    pub fn origin_mode(&self) -> bool {
+        self.origin_mode
+    }
+
+    /// Switch wrapping at the right margin on or off (DECAWM)
+    ///
+    /// flanterm ignores DECAWM and always wraps, so with the bundled library
+    /// this only records the mode (see [`auto_wrap`](Self::auto_wrap)) and
+    /// forwards `\x1b[?7h`/`\x1b[?7l` to mirrors of the output. Code that
+    /// must not wrap has to limit line lengths itself.
+    pub fn set_auto_wrap(&mut self, enabled: bool) {
+        self.write_bytes(if enabled { b"\x1b[?7h" } else { b"\x1b[?7l" });
+        self.auto_wrap = enabled;
+    }
+
+    /// Check whether auto-wrap was last requested on
+    pub fn auto_wrap(&self) -> bool {
+        self.auto_wrap
+    }
+
+    /// First row addressable by `move_cursor` and how many rows follow it
+    pub(crate) fn addressable_rows(&self) -> (usize, usize) {
+        match self.scroll_region {
+            Some((top, bottom)) if self.origin_mode => (top, bottom - top + 1),
+            _ => (0, self.get_dimensions().1),
+        }
+    }
+
+    /// Move the cursor up `n` rows, stopping at the top row
+    pub fn cursor_up(&mut self, n: usize) {
+        let rows = self.get_dimensions().1;
+        self.cursor_relative(n.min(rows), 'A');
+    }
+
+    /// Move the cursor down `n` rows, stopping at the bottom row
+    pub fn cursor_down(&mut self, n: usize) {
+        let rows = self.get_dimensions().1;
+        self.cursor_relative(n.min(rows), 'B');
+    }
+
+    /// Move the cursor right `n` columns, stopping at the last column
+    pub fn cursor_forward(&mut self, n: usize) {
+        let cols = self.get_dimensions().0;
+        self.cursor_relative(n.min(cols), 'C');
+    }
+
+    /// Move the cursor left `n` columns, stopping at the first column
+    pub fn cursor_back(&mut self, n: usize) {
+        let cols = self.get_dimensions().0;
+        self.cursor_relative(n.min(cols), 'D');
+    }
+
+    /// Move the cursor to the first column of the current row
+    pub fn cursor_to_line_start(&mut self) {
+        self.write_bytes(b"\r");
+    }
+
+    /// Move the cursor down one row, scrolling up at the bottom margin
+    pub fn newline(&mut self) {
+        self.write_bytes(b"\n");
+    }
+
+    /// Move the cursor up one row, scrolling down at the top margin (RI)
+    ///
+    /// At the top margin the scroll region moves down and a blank line is
+    /// inserted at the top. Unlike most terminals, flanterm also moves the
+    /// cursor to the first column, in both cases.
+    pub fn reverse_newline(&mut self) {
+        self.write_bytes(b"\x1bM");
+    }
+
+    /// Move the cursor to the first column `n` rows down, stopping at the bottom row
+    pub fn cursor_next_line(&mut self, n: usize) {
+        let rows = self.get_dimensions().1;
+        self.cursor_relative(n.min(rows), 'E');
+    }
+
+    /// Move the cursor to the first column `n` rows up, stopping at the top row
+    pub fn cursor_prev_line(&mut self, n: usize) {
+        let rows = self.get_dimensions().1;
+        self.cursor_relative(n.min(rows), 'F');
+    }
+
+    /// Write a relative move; flanterm takes a count of 0 literally, so empty moves are skipped
+    fn cursor_relative(&mut self, n: usize, command: char) {
+        if n > 0 {
+            let _ = write!(self, "\x1b[{}{}", n, command);
+        }
+    }
+
+    /// Remember the cursor position for [`restore_cursor`](Self::restore_cursor)
+    ///
+    /// Uses `\x1b[s`, which saves only the position. There is a single save
+    /// slot, so a second save overwrites the first; `\x1b7` is a separate
+    /// slot that also saves colors and attributes.
+    pub fn save_cursor(&mut self) {
+        self.write_bytes(b"\x1b[s");
+    }
+
+    /// Move the cursor back to the position saved by [`save_cursor`](Self::save_cursor)
+    pub fn restore_cursor(&mut self) {
+        self.write_bytes(b"\x1b[u");
+    }
+
+    /// Move the cursor to 0-indexed column `col` of the current row (CHA)
+    ///
+    /// Columns past the right edge are clamped to the last one.
+    pub fn cursor_to_column(&mut self, col: usize) {
+        let cols = self.get_dimensions().0;
+        let _ = write!(self, "\x1b[{}G", col.min(cols.saturating_sub(1)) + 1);
+    }
+
+    /// Move the cursor forward `n` tab stops (CHT), stopping at the last column
+    ///
+    /// flanterm ignores CHT, so this writes `n` tab characters instead,
+    /// which move identically.
+    pub fn cursor_next_tab(&mut self, n: usize) {
+        let cols = self.get_dimensions().0;
+        for _ in 0..n.min(cols) {
+            self.write_bytes(b"\t");
+        }
+    }
+
+    /// Move the cursor back `n` tab stops (CBT), stopping at the first column
+    ///
+    /// flanterm ignores CBT, so the target column is computed from the tab
+    /// width and reached with [`cursor_to_column`](Self::cursor_to_column).
+    pub fn cursor_prev_tab(&mut self, n: usize) {
+        if self.ctx.is_null() || n == 0 {
+            return;
+        }
+        let tab_size = unsafe { flanterm_rs_get_tab_size(self.ctx) };
+        let (x, _) = self.get_cursor_pos();
+        let stop = (x.saturating_sub(1) / tab_size).saturating_sub(n - 1);
+        self.cursor_to_column(stop * tab_size);
+    }
+
+    /// Set a tab stop at the cursor column (HTS)
+    ///
+    /// flanterm has no tab stop list, it only supports evenly spaced stops,
+    /// so it ignores this; use [`set_tab_width`](Self::set_tab_width) instead.
+    pub fn set_tab_stop(&mut self) {
+        self.write_bytes(b"\x1bH");
+    }
+
+    /// Clear the tab stop at the cursor column (TBC 0); ignored by flanterm
+    pub fn clear_tab_stop(&mut self) {
+        self.write_bytes(b"\x1b[0g");
+    }
+
+    /// Clear every tab stop (TBC 3); ignored by flanterm
+    pub fn clear_all_tab_stops(&mut self) {
+        self.write_bytes(b"\x1b[3g");
+    }
+
+    /// Place tab stops every `n` columns instead of the default 8
+    ///
+    /// Since flanterm only supports evenly spaced stops, this sets the
+    /// interval directly rather than emitting HTS for each column. A width of
+    /// 0 is ignored. A full reset (`\x1bc`) goes back to 8.
+    pub fn set_tab_width(&mut self, n: usize) {
+        if self.ctx.is_null() || n == 0 {
+            return;
+        }
+        self.drain_write_buffer();
+        unsafe {
+            flanterm_rs_set_tab_size(self.ctx, n);
+        }
+    }
+
+    /// Show or hide the cursor (DEC private mode 25)
+    ///
+    /// flanterm implements this mode itself: a hidden cursor is simply not
+    /// drawn on the next flush, which avoids a block flickering across the
+    /// screen during large redraws.
+    pub fn set_cursor_visible(&mut self, visible: bool) {
+        self.write_bytes(if visible { b"\x1b[?25h" } else { b"\x1b[?25l" });
+    }
+
+    /// Request a cursor shape with DECSCUSR (`\x1b[N q`)
+    ///
+    /// Whether DECSCUSR is honored depends on the flanterm version. The
+    /// bundled one always draws a steady block and does not parse it: it
+    /// ends the sequence at the space and prints the trailing `q` as text.
+    /// The sequence is therefore never passed to flanterm, only offered to
+    /// the [interceptor](Self::set_interceptor), so a terminal the output is
+    /// mirrored to can still switch shapes. Without an interceptor this does
+    /// nothing.
+    pub fn set_cursor_shape(&mut self, shape: CursorShape) {
+        if !self.enabled {
+            return;
+        }
+        if let Some(scanner) = &mut self.interceptor {
+            scanner.offer(&[0x1b, b'[', b'0' + shape.param(), b' ', b'q']);
+        }
+    }
+}
+
+/// Cursor styles selectable with DECSCUSR (`\x1b[N q`)
+///
+/// See [`FlantermContext::set_cursor_shape`] for how far flanterm supports
+/// it; [`param`](Self::param) gives the `N` to send to other terminals.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CursorShape {
+    /// The terminal's own default shape
+    Default,
+    BlockBlink,
+    BlockSteady,
+    UnderlineBlink,
+    UnderlineSteady,
+    BarBlink,
+    BarSteady,
+}
+
+impl CursorShape {
+    /// The DECSCUSR parameter `N` selecting this shape
+    pub const fn param(self) -> u8 {
+        match self {
+            Self::Default => 0,
+            Self::BlockBlink => 1,
+            Self::BlockSteady => 2,
+            Self::UnderlineBlink => 3,
+            Self::UnderlineSteady => 4,
+            Self::BarBlink => 5,
+            Self::BarSteady => 6,
+        }
+    }
+}
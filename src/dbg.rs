@@ -0,0 +1,29 @@
+use crate::{AnsiColor, FlantermContext, with_global_flanterm};
+use core::fmt::{self, Write};
+
+/// Print `[file:line] expr = value` in yellow for [`dbg_terminal!`](crate::dbg_terminal)
+///
+/// Goes to the [global instance](crate::init_global_flanterm) if `ctx` is `None`.
+pub fn _dbg_terminal(
+    ctx: Option<&mut FlantermContext>,
+    file: &str,
+    line: u32,
+    expr: Option<(&str, &dyn fmt::Debug)>,
+) {
+    let print = |ctx: &mut FlantermContext| {
+        ctx.push_attrs();
+        ctx.set_ansi_fg(AnsiColor::Yellow);
+        let _ = match expr {
+            Some((expr, value)) => write!(ctx, "[{file}:{line}] {expr} = {value:?}"),
+            None => write!(ctx, "[{file}:{line}]"),
+        };
+        ctx.pop_attrs();
+        let _ = ctx.write_str("\n");
+    };
+    match ctx {
+        Some(ctx) => print(ctx),
+        None => {
+            with_global_flanterm(print);
+        }
+    }
+}
@@ -0,0 +1,46 @@
+use crate::FlantermContext;
+use core::fmt::Write;
+
+/// Commonly used DEC private modes
+///
+/// flanterm itself only implements `ShowCursor`; the wrapper emulates
+/// `OriginMode` and `AltScreen` and records `AutoWrap`. Every other mode is
+/// handed to the flanterm callback as `FLANTERM_CB_DEC` and otherwise
+/// ignored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u16)]
+pub enum DecMode {
+    OriginMode = 6,
+    AutoWrap = 7,
+    ShowCursor = 25,
+    MouseAny = 1003,
+    FocusEvents = 1004,
+    AltScreen = 1049,
+    BracketedPaste = 2004,
+}
+
+impl From<DecMode> for u16 {
+    fn from(mode: DecMode) -> Self {
+        mode as u16
+    }
+}
+
+impl FlantermContext {
+    /// Set or reset a DEC private mode (`\x1b[?Nh` / `\x1b[?Nl`)
+    ///
+    /// Accepts a [`DecMode`] or any raw mode number. Modes the wrapper
+    /// tracks itself go through their dedicated setters, e.g.
+    /// [`set_origin_mode`](Self::set_origin_mode) or
+    /// [`enter_alt_screen`](Self::enter_alt_screen).
+    pub fn set_dec_mode(&mut self, mode: impl Into<u16>, enabled: bool) {
+        match mode.into() {
+            6 => self.set_origin_mode(enabled),
+            7 => self.set_auto_wrap(enabled),
+            1049 if enabled => self.enter_alt_screen(),
+            1049 => self.leave_alt_screen(),
+            mode => {
+                let _ = write!(self, "\x1b[?{}{}", mode, if enabled { 'h' } else { 'l' });
+            }
+        }
+    }
+}
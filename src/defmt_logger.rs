@@ -0,0 +1,66 @@
+//! A [`defmt`] global logger printing to the global flanterm instance
+//!
+//! defmt does not format anything on the target: log frames hold indices
+//! into a string table in the ELF file and the arguments in binary. Each
+//! frame is printed as one line of hex, so it can be captured from the
+//! screen and decoded on a host with the
+//! [`defmt-decoder`](https://docs.rs/defmt-decoder) crate and the ELF
+//! file. See the [defmt book](https://defmt.ferrous-systems.com/) for how
+//! to set up the linker script and the rest of defmt.
+//!
+//! The hex is the encoder's output as is: with defmt's default encoding
+//! each frame is rzCOBS-encoded and ends in a `00` delimiter byte, and
+//! `defmt-print` expects exactly that byte stream. Turn the captured lines
+//! back into binary before decoding, dropping the newlines, e.g.
+//! `xxd -r -p capture.txt | defmt-print -e kernel.elf`. `defmt-print`
+//! reads the encoding from the ELF file, so no further options are needed.
+//!
+//! The logger is registered as soon as the `defmt` feature is enabled, so
+//! no other defmt global logger, such as `defmt-rtt`, can be linked in.
+
+use crate::with_global_flanterm;
+use core::fmt::Write;
+use core::sync::atomic::{AtomicBool, Ordering};
+use spin::mutex::Mutex;
+
+/// Set while a frame is being written
+static TAKEN: AtomicBool = AtomicBool::new(false);
+
+static ENCODER: Mutex<defmt::Encoder> = Mutex::new(defmt::Encoder::new());
+
+#[defmt::global_logger]
+struct FlantermDefmtLogger;
+
+/// Print encoded frame bytes as hex
+fn write_hex(bytes: &[u8]) {
+    with_global_flanterm(|ctx| {
+        for byte in bytes {
+            let _ = write!(ctx, "{byte:02x}");
+        }
+    });
+}
+
+unsafe impl defmt::Logger for FlantermDefmtLogger {
+    fn acquire() {
+        if TAKEN.swap(true, Ordering::Acquire) {
+            panic!("defmt logger taken reentrantly");
+        }
+        ENCODER.lock().start_frame(write_hex);
+    }
+
+    unsafe fn flush() {
+        with_global_flanterm(|ctx| ctx.flush());
+    }
+
+    unsafe fn release() {
+        ENCODER.lock().end_frame(write_hex);
+        with_global_flanterm(|ctx| {
+            let _ = ctx.write_str("\n");
+        });
+        TAKEN.store(false, Ordering::Release);
+    }
+
+    unsafe fn write(bytes: &[u8]) {
+        ENCODER.lock().write(bytes, write_hex);
+    }
+}
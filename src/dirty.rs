@@ -0,0 +1,120 @@
+use crate::FlantermContext;
+use crate::bindings::*;
+use core::ptr;
+
+/// A pixel rectangle as (x, y, w, h)
+pub(crate) type Rect = (usize, usize, usize, usize);
+
+/// The smallest rectangle covering both `a` and `b`
+fn union(a: Option<Rect>, b: Rect) -> Rect {
+    let Some((ax, ay, aw, ah)) = a else {
+        return b;
+    };
+    let (bx, by, bw, bh) = b;
+    let x = ax.min(bx);
+    let y = ay.min(by);
+    let right = (ax + aw).max(bx + bw);
+    let bottom = (ay + ah).max(by + bh);
+    (x, y, right - x, bottom - y)
+}
+
+impl FlantermContext {
+    /// Start recording which part of the framebuffer changes
+    ///
+    /// Everything drawn from then on is collected into one bounding box,
+    /// read with [`get_and_clear_dirty_rect`](Self::get_and_clear_dirty_rect).
+    /// That covers the cells flanterm redraws on [`flush`](Self::flush),
+    /// pixels written through [`put_pixel`](Self::put_pixel) and friends,
+    /// and the whole screen on [`full_refresh`](Self::full_refresh). flanterm
+    /// does not report its own flushes, so the wrapper takes over
+    /// [autoflush](Self::set_autoflush) and flushes after each write instead.
+    /// Output through a [`FlantermHandle`](crate::FlantermHandle) is not seen.
+    pub fn enable_dirty_tracking(&mut self) {
+        if self.ctx.is_null() {
+            return;
+        }
+        self.take_over_flushing();
+        self.dirty_tracking = true;
+    }
+
+    /// Stop recording changes and drop the rectangle collected so far
+    pub fn disable_dirty_tracking(&mut self) {
+        self.dirty_tracking = false;
+        self.dirty_rect = None;
+        self.release_flushing();
+    }
+
+    /// Get the bounding box of everything drawn since the last call as (x, y, w, h) in pixels
+    ///
+    /// Returns `None` if nothing changed or [tracking](Self::enable_dirty_tracking)
+    /// is off. Output that is still waiting for a flush has not changed any
+    /// pixels yet. With [double buffering](Self::enable_double_buffer) the
+    /// rectangle refers to the back buffer.
+    pub fn get_and_clear_dirty_rect(&mut self) -> Option<(usize, usize, usize, usize)> {
+        self.dirty_rect.take()
+    }
+
+    /// Switch flanterm's autoflush off and apply the setting in the wrapper instead
+    ///
+    /// Needed whenever flushes have to be seen, since flanterm's own happen
+    /// inside `flanterm_write`.
+    pub(crate) fn take_over_flushing(&mut self) {
+        if self.managed_autoflush.is_none() {
+            unsafe {
+                self.managed_autoflush = Some(flanterm_rs_get_autoflush(self.ctx));
+                flanterm_set_autoflush(self.ctx, false);
+            }
+        }
+    }
+
+    /// Give autoflush back to flanterm once nothing needs to see the flushes
+    pub(crate) fn release_flushing(&mut self) {
+        if self.double_buffer.is_some() || self.dirty_tracking || self.selective_refresh.is_some() {
+            return;
+        }
+        if let Some(autoflush) = self.managed_autoflush.take()
+            && !self.ctx.is_null()
+        {
+            unsafe {
+                flanterm_set_autoflush(self.ctx, autoflush);
+            }
+        }
+    }
+
+    /// Flush, recording what flanterm redrew, and copy it to the screen if double buffered
+    ///
+    /// Changed cells are also marked for selective refresh.
+    pub(crate) fn tracked_flush(&mut self) {
+        let (mut x, mut y, mut w, mut h) = (0, 0, 0, 0);
+        let (cells, cells_len) = match &mut self.selective_refresh {
+            Some(map) => (map.bits.as_mut_ptr(), map.cells()),
+            None => (ptr::null_mut(), 0),
+        };
+        if unsafe {
+            flanterm_rs_fb_flush_bounds(self.ctx, &mut x, &mut y, &mut w, &mut h, cells, cells_len)
+        } {
+            self.mark_dirty((x, y, w, h));
+        }
+        self.present();
+    }
+
+    /// Record that a rectangle of the surface changed
+    pub(crate) fn mark_dirty(&mut self, rect: Rect) {
+        if rect.2 == 0 || rect.3 == 0 {
+            return;
+        }
+        if let Some(db) = &mut self.double_buffer {
+            db.dirty = Some(union(db.dirty, rect));
+        }
+        if self.dirty_tracking {
+            self.dirty_rect = Some(union(self.dirty_rect, rect));
+        }
+    }
+
+    /// Record that the whole surface changed
+    pub(crate) fn mark_all_dirty(&mut self) {
+        if let Some(surface) = self.surface() {
+            self.mark_dirty((0, 0, surface.width, surface.height));
+        }
+    }
+}
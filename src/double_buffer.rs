@@ -0,0 +1,138 @@
+use crate::bindings::*;
+use crate::dirty::Rect;
+use crate::{DoubleBufferError, FlantermContext};
+use core::ptr;
+
+/// Where the back buffer is copied to
+pub(crate) enum Front {
+    /// The framebuffer flanterm was created with, in the same format
+    Primary(*mut u32),
+    /// A 16bpp framebuffer, converted to on the way
+    #[cfg(feature = "fb16bpp")]
+    Rgb565 { framebuffer: *mut u16, pitch: usize },
+}
+
+/// State of a context drawing into a back buffer
+pub(crate) struct DoubleBuffer {
+    pub(crate) front: Front,
+    pub(crate) back: *mut u32,
+    /// Part of the back buffer not yet copied to the front framebuffer
+    pub(crate) dirty: Option<Rect>,
+}
+
+impl FlantermContext {
+    /// Draw into `back_buffer` instead of the framebuffer, copying to the screen on flush
+    ///
+    /// `len` is the size of the back buffer in bytes and must be at least
+    /// that of the framebuffer (`height * pitch`); rows use the
+    /// framebuffer's pitch. The current screen is redrawn into it straight
+    /// away.
+    ///
+    /// Afterwards output only reaches the screen on [`flush`](Self::flush)
+    /// or [`full_refresh`](Self::full_refresh), which copy what changed
+    /// since the last one in a single pass, so large redraws never show up
+    /// half done. With [autoflush](Self::set_autoflush) on this happens
+    /// after every write. Pixels drawn through [`put_pixel`](Self::put_pixel)
+    /// and friends go to the back buffer too. Enabling it again switches to
+    /// the new buffer.
+    ///
+    /// # Safety
+    ///
+    /// `back_buffer` must be 4-byte aligned, valid for reads and writes of
+    /// `len` bytes and not used by anything else until double buffering is
+    /// [disabled](Self::disable_double_buffer) or the context is dropped.
+    pub unsafe fn enable_double_buffer(
+        &mut self,
+        back_buffer: *mut u32,
+        len: usize,
+    ) -> Result<(), DoubleBufferError> {
+        let surface = self.surface().ok_or(DoubleBufferError::InvalidContext)?;
+        if back_buffer.is_null() {
+            return Err(DoubleBufferError::NullBuffer);
+        }
+        let min_required = surface.height * surface.pitch;
+        if len < min_required {
+            return Err(DoubleBufferError::BufferTooSmall { len, min_required });
+        }
+        let primary = match self.double_buffer.as_ref().map(|db| &db.front) {
+            None => surface.framebuffer,
+            Some(Front::Primary(primary)) => *primary,
+            #[cfg(feature = "fb16bpp")]
+            Some(Front::Rgb565 { .. }) => return Err(DoubleBufferError::Fb16),
+        };
+        self.disable_double_buffer();
+        self.take_over_flushing();
+        // Pending output still belongs on the primary framebuffer
+        self.tracked_flush();
+
+        unsafe {
+            flanterm_rs_fb_set_framebuffer(self.ctx, back_buffer);
+            flanterm_full_refresh(self.ctx);
+        }
+        self.double_buffer = Some(DoubleBuffer {
+            front: Front::Primary(primary),
+            back: back_buffer,
+            dirty: None,
+        });
+        Ok(())
+    }
+
+    /// Copy any pending output to the screen and draw to the framebuffer directly again
+    ///
+    /// Does nothing for a [16bpp context](Self::new_fb16), which always draws
+    /// through its back buffer.
+    pub fn disable_double_buffer(&mut self) {
+        let Some(DoubleBuffer {
+            front: Front::Primary(primary),
+            ..
+        }) = self.double_buffer
+        else {
+            return;
+        };
+        self.flush();
+        self.double_buffer = None;
+        if !self.ctx.is_null() {
+            unsafe {
+                flanterm_rs_fb_set_framebuffer(self.ctx, primary);
+            }
+        }
+        self.release_flushing();
+    }
+
+    /// Check whether output goes to a back buffer set with [`enable_double_buffer`](Self::enable_double_buffer)
+    pub fn is_double_buffered(&self) -> bool {
+        self.double_buffer.is_some()
+    }
+
+    /// Copy the dirty part of the back buffer to the front framebuffer
+    pub(crate) fn present(&mut self) {
+        let Some(surface) = self.surface() else {
+            return;
+        };
+        let Some(db) = &mut self.double_buffer else {
+            return;
+        };
+        let Some((x, y, w, h)) = db.dirty.take() else {
+            return;
+        };
+        let w = w.min(surface.width.saturating_sub(x));
+        let h = h.min(surface.height.saturating_sub(y));
+        for row in y..y + h {
+            let offset = row * surface.pitch + x * 4;
+            let src = unsafe { db.back.byte_add(offset) };
+            match db.front {
+                Front::Primary(primary) => unsafe {
+                    ptr::copy_nonoverlapping(src, primary.byte_add(offset), w);
+                },
+                #[cfg(feature = "fb16bpp")]
+                Front::Rgb565 { framebuffer, pitch } => unsafe {
+                    let dst = framebuffer.byte_add(row * pitch).add(x);
+                    for i in 0..w {
+                        dst.add(i)
+                            .write_volatile(crate::fb16::to_rgb565(src.add(i).read()));
+                    }
+                },
+            }
+        }
+    }
+}
@@ -0,0 +1,224 @@
+use crate::text::{ClippedWriter, ellipsize};
+use crate::unicode::{str_width, truncate_to_width};
+use crate::{ColorSpec, FlantermContext, TextAttributes};
+use core::fmt::Write;
+
+/// Line style for [`FlantermContext::draw_box`]
+///
+/// flanterm's fonts cover code page 437, which has glyphs for `Light`,
+/// `Double` and `Ascii` but none for the heavy lines or rounded corners,
+/// so flanterm draws `Heavy` and `Rounded` boxes with placeholder blocks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BoxStyle {
+    #[default]
+    Light,
+    Heavy,
+    Double,
+    Rounded,
+    /// `+`, `-` and `|`
+    Ascii,
+}
+
+impl BoxStyle {
+    /// Top left, top right, bottom left and bottom right corners, then the horizontal and vertical lines
+    const fn chars(self) -> [char; 6] {
+        match self {
+            Self::Light => ['┌', '┐', '└', '┘', '─', '│'],
+            Self::Heavy => ['┏', '┓', '┗', '┛', '━', '┃'],
+            Self::Double => ['╔', '╗', '╚', '╝', '═', '║'],
+            Self::Rounded => ['╭', '╮', '╰', '╯', '─', '│'],
+            Self::Ascii => ['+', '+', '+', '+', '-', '|'],
+        }
+    }
+}
+
+/// Characters a progress bar is drawn with, for [`FlantermContext::draw_progress_bar`]
+///
+/// All of them have glyphs in flanterm's code page 437 fonts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ProgressStyle {
+    /// `#` for done and `-` for remaining cells
+    #[default]
+    Hash,
+    /// Solid `█` blocks, remaining cells left blank
+    Block,
+    /// `▓` for done and `░` for remaining cells, with a `▒` cell that is at least half done
+    Shaded,
+}
+
+impl ProgressStyle {
+    /// Done, half done and remaining cells
+    const fn chars(self) -> [char; 3] {
+        match self {
+            Self::Hash => ['#', '-', '-'],
+            Self::Block => ['█', ' ', ' '],
+            Self::Shaded => ['▓', '▒', '░'],
+        }
+    }
+}
+
+impl FlantermContext {
+    /// Fill `row` with `ch` in the given palette colors
+    ///
+    /// `None` keeps the current color. The cursor and attributes are
+    /// restored afterwards.
+    pub fn draw_hline_char(&mut self, row: usize, ch: char, fg: Option<u8>, bg: Option<u8>) {
+        let attrs = TextAttributes {
+            fg: fg.map(ColorSpec::Palette),
+            bg: bg.map(ColorSpec::Palette),
+            ..TextAttributes::default()
+        };
+        self.push_attrs();
+        attrs.apply(self);
+        self.at_position(0, row, |ctx, cols| {
+            let _ = ctx.write_char_n(ch, cols / Self::char_display_width(ch).max(1));
+        });
+        self.pop_attrs();
+    }
+
+    /// Fill `row` with copies of `fill`, cutting the last one off at the right edge
+    ///
+    /// The cursor is restored afterwards.
+    pub fn draw_hline_str(&mut self, row: usize, fill: &str) {
+        self.at_position(0, row, |ctx, mut cols| {
+            while cols > 0 {
+                let part = truncate_to_width(fill, cols);
+                let width = str_width(part);
+                if width == 0 {
+                    break;
+                }
+                ctx.write_bytes(part.as_bytes());
+                cols -= width;
+            }
+        });
+    }
+
+    /// Draw the border of a `w`x`h` box with its top left corner at (`x`, `y`)
+    ///
+    /// The interior is left as it is. Parts of the box off screen are
+    /// clipped, boxes smaller than 2x2 are not drawn, and the cursor is
+    /// restored afterwards.
+    pub fn draw_box(&mut self, x: usize, y: usize, w: usize, h: usize, style: BoxStyle) {
+        if w < 2 || h < 2 {
+            return;
+        }
+        let [
+            top_left,
+            top_right,
+            bottom_left,
+            bottom_right,
+            horizontal,
+            vertical,
+        ] = style.chars();
+        let rows = self.get_dimensions().1;
+        self.at_position(x, y, |ctx, room| {
+            let bottom = y + h - 1;
+            ctx.box_edge(room, w, [top_left, horizontal, top_right]);
+            for row in y + 1..bottom.min(rows) {
+                let _ = write!(ctx, "\x1b[{};{}H", row + 1, x + 1);
+                let _ = ctx.write_char(vertical);
+                if w <= room {
+                    let _ = write!(ctx, "\x1b[{}G", x + w);
+                    let _ = ctx.write_char(vertical);
+                }
+            }
+            if bottom < rows {
+                let _ = write!(ctx, "\x1b[{};{}H", bottom + 1, x + 1);
+                ctx.box_edge(room, w, [bottom_left, horizontal, bottom_right]);
+            }
+        });
+    }
+
+    /// Like [`draw_box`](Self::draw_box) with `title` centered in the top border
+    ///
+    /// The title is surrounded by a space on each side and cut off with
+    /// `...` if the box is too narrow for it.
+    pub fn draw_box_labeled(
+        &mut self,
+        x: usize,
+        y: usize,
+        w: usize,
+        h: usize,
+        style: BoxStyle,
+        title: &str,
+    ) {
+        self.draw_box(x, y, w, h, style);
+        if w < 5 || h < 2 {
+            return;
+        }
+        let (text, dots) = ellipsize(title, w - 4);
+        let label_cols = str_width(text) + dots + 2;
+        let col = x + 1 + (w - 2 - label_cols) / 2;
+        self.at_position(col, y, |ctx, room| {
+            let mut out = ClippedWriter::new(ctx, room);
+            let _ = write!(out, " {}{} ", text, &"..."[..dots]);
+        });
+    }
+
+    /// Draw one horizontal edge of a box at the cursor, clipped to `room` columns
+    fn box_edge(&mut self, room: usize, w: usize, [left, middle, right]: [char; 3]) {
+        let _ = self.write_char(left);
+        let _ = self.write_char_n(middle, (w - 2).min(room - 1));
+        if w <= room {
+            let _ = self.write_char(right);
+        }
+    }
+
+    /// Draw a `width` cell progress bar starting at (`col`, `row`)
+    ///
+    /// `progress` is clamped to 0.0-1.0 and fills `progress * width` whole
+    /// cells. The bar is clipped at the right edge of the screen and the
+    /// cursor is restored afterwards.
+    pub fn draw_progress_bar(
+        &mut self,
+        col: usize,
+        row: usize,
+        width: usize,
+        progress: f32,
+        style: ProgressStyle,
+    ) {
+        self.at_position(col, row, |ctx, room| {
+            ctx.progress_bar(room, width, progress, style);
+        });
+    }
+
+    /// Like [`draw_progress_bar`](Self::draw_progress_bar), followed by the percentage and `label`
+    pub fn draw_progress_bar_labeled(
+        &mut self,
+        col: usize,
+        row: usize,
+        width: usize,
+        progress: f32,
+        style: ProgressStyle,
+        label: &str,
+    ) {
+        self.at_position(col, row, |ctx, room| {
+            let room = room - ctx.progress_bar(room, width, progress, style);
+            let percent = (progress.clamp(0.0, 1.0) * 100.0) as u32;
+            let mut out = ClippedWriter::new(ctx, room);
+            let _ = write!(out, " {percent:>3}% {label}");
+        });
+    }
+
+    /// Draw the bar at the cursor, returning how many of the `room` columns it took
+    fn progress_bar(
+        &mut self,
+        room: usize,
+        width: usize,
+        progress: f32,
+        style: ProgressStyle,
+    ) -> usize {
+        let [done, half, remaining] = style.chars();
+        let cells = progress.clamp(0.0, 1.0) * width as f32;
+        let full = cells as usize;
+        let half_cells = usize::from(full < width && cells - full as f32 >= 0.5);
+
+        let width = width.min(room);
+        let full = full.min(width);
+        let half_cells = half_cells.min(width - full);
+        let _ = self.write_char_n(done, full);
+        let _ = self.write_char_n(half, half_cells);
+        let _ = self.write_char_n(remaining, width - full - half_cells);
+        width
+    }
+}
@@ -0,0 +1,30 @@
+//! Bitmap fonts built into the crate
+//!
+//! Both are PSF1 files with 256 glyphs in CP437 order, so they can be
+//! passed to [`FlantermContextBuilder::psf_font`](crate::FlantermContextBuilder::psf_font)
+//! as they are.
+
+use crate::fonts::{PsfFont, load_psf1};
+
+/// The VGA text mode font as a PSF1 file, 256 glyphs of 8x16 pixels
+///
+/// These are the same glyphs flanterm draws with when no font is given.
+pub static FONT_8X16: &[u8] = include_bytes!("../fonts/font8x16.psf");
+
+/// A compact font as a PSF1 file, 256 glyphs of 8x8 pixels
+///
+/// Made from [`FONT_8X16`] by merging each pair of rows, for small
+/// screens where 16 pixel high cells leave too few rows.
+pub static FONT_8X8: &[u8] = include_bytes!("../fonts/font8x8.psf");
+
+/// [`FONT_8X16`] parsed
+pub const PSF_FONT_8X16: PsfFont = match load_psf1(FONT_8X16) {
+    Some(font) => font,
+    None => panic!("invalid embedded 8x16 font"),
+};
+
+/// [`FONT_8X8`] parsed
+pub const PSF_FONT_8X8: PsfFont = match load_psf1(FONT_8X8) {
+    Some(font) => font,
+    None => panic!("invalid embedded 8x8 font"),
+};
@@ -0,0 +1,38 @@
+use crate::{AnsiColor, ContextId, with_global_flanterm};
+use core::fmt::{self, Write};
+use spin::mutex::Mutex;
+
+/// Where [`eprint!`](crate::eprint) writes to, if set
+static ERROR_CONTEXT: Mutex<Option<ContextId>> = Mutex::new(None);
+
+/// Send [`eprint!`](crate::eprint) and [`eprintln!`](crate::eprintln) output to `id`
+///
+/// While no error context is set, or the one set does not exist, error
+/// output goes to the [global instance](crate::init_global_flanterm) in
+/// bold red.
+pub fn set_error_context(id: ContextId) {
+    *ERROR_CONTEXT.lock() = Some(id);
+}
+
+/// Send error output back to the global instance in bold red
+pub fn clear_error_context() {
+    *ERROR_CONTEXT.lock() = None;
+}
+
+/// Print to the error context
+pub fn _eprint(args: fmt::Arguments) {
+    let id = *ERROR_CONTEXT.lock();
+    if id
+        .and_then(|id| id.with(|ctx| ctx.write_fmt(args)))
+        .is_some()
+    {
+        return;
+    }
+    with_global_flanterm(|ctx| {
+        ctx.push_attrs();
+        ctx.set_bold();
+        ctx.set_ansi_fg(AnsiColor::Red);
+        let _ = ctx.write_fmt(args);
+        ctx.pop_attrs();
+    });
+}
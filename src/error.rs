@@ -0,0 +1,184 @@
+use core::fmt;
+
+/// Reasons creating a framebuffer context can fail
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FbInitError {
+    /// The framebuffer pointer is null
+    NullFramebuffer,
+    /// The framebuffer width is zero
+    ZeroWidth,
+    /// The framebuffer height is zero
+    ZeroHeight,
+    /// The pitch is too small to hold `width` 32-bit pixels
+    InvalidPitch { pitch: usize, min_required: usize },
+    /// flanterm only supports equal channel sizes of at least 8 bits
+    UnsupportedPixelFormat,
+    /// `flanterm_fb_init` failed, usually because it ran out of memory
+    FlantermAllocFailed,
+    /// A custom font was given with dimensions flanterm cannot use
+    InvalidFont,
+}
+
+impl fmt::Display for FbInitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NullFramebuffer => f.write_str("framebuffer pointer is null"),
+            Self::ZeroWidth => f.write_str("framebuffer width is zero"),
+            Self::ZeroHeight => f.write_str("framebuffer height is zero"),
+            Self::InvalidPitch {
+                pitch,
+                min_required,
+            } => write!(f, "pitch {pitch} is below the minimum of {min_required}"),
+            Self::UnsupportedPixelFormat => f.write_str("unsupported pixel format"),
+            Self::FlantermAllocFailed => f.write_str("flanterm failed to allocate the context"),
+            Self::InvalidFont => f.write_str("invalid font dimensions"),
+        }
+    }
+}
+
+impl core::error::Error for FbInitError {}
+
+/// Reasons [`FlantermContext::reinit_fb`](crate::FlantermContext::reinit_fb) can fail
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReinitError {
+    /// The context was not built by this crate, so there is no configuration to reuse
+    NoConfig,
+    /// The context uses flanterm's static bump allocator, which cannot host a second instance
+    NoAllocator,
+    /// The context configuration with the new framebuffer failed validation
+    InvalidConfig(FbInitError),
+    /// `flanterm_fb_init` failed; the context is left invalid
+    InitFailed,
+}
+
+impl fmt::Display for ReinitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoConfig => f.write_str("context has no stored configuration"),
+            Self::NoAllocator => f.write_str("context was built without a custom allocator"),
+            Self::InvalidConfig(err) => write!(f, "invalid configuration: {err}"),
+            Self::InitFailed => f.write_str("flanterm failed to initialize"),
+        }
+    }
+}
+
+impl core::error::Error for ReinitError {}
+
+/// A cursor position outside the terminal, as rejected by
+/// [`FlantermContext::try_move_cursor`](crate::FlantermContext::try_move_cursor)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CursorOutOfBoundsError {
+    pub x: usize,
+    pub y: usize,
+    /// Terminal width in columns
+    pub cols: usize,
+    /// Terminal height in rows
+    pub rows: usize,
+}
+
+impl fmt::Display for CursorOutOfBoundsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "cursor position ({}, {}) is outside the {}x{} terminal",
+            self.x, self.y, self.cols, self.rows
+        )
+    }
+}
+
+impl core::error::Error for CursorOutOfBoundsError {}
+
+/// Malformed UTF-8 rejected by [`FlantermContext::try_write_bytes`](crate::FlantermContext::try_write_bytes)
+/// in strict mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidUtf8Error {
+    /// Number of bytes of the rejected write that were passed on before the invalid sequence
+    pub valid_up_to: usize,
+}
+
+impl fmt::Display for InvalidUtf8Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid UTF-8 after {} bytes", self.valid_up_to)
+    }
+}
+
+impl core::error::Error for InvalidUtf8Error {}
+
+/// A pixel position outside the framebuffer, as rejected by
+/// [`FlantermContext::put_pixel`](crate::FlantermContext::put_pixel)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OutOfBoundsError {
+    pub x: usize,
+    pub y: usize,
+    /// Framebuffer width in pixels
+    pub width: usize,
+    /// Framebuffer height in pixels
+    pub height: usize,
+}
+
+impl fmt::Display for OutOfBoundsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "pixel ({}, {}) is outside the {}x{} framebuffer",
+            self.x, self.y, self.width, self.height
+        )
+    }
+}
+
+impl core::error::Error for OutOfBoundsError {}
+
+/// Reasons [`FlantermContext::enable_double_buffer`](crate::FlantermContext::enable_double_buffer) can fail
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DoubleBufferError {
+    /// The context is invalid, so there is no framebuffer to stand in for
+    InvalidContext,
+    /// The back buffer pointer is null
+    NullBuffer,
+    /// The back buffer is shorter than the framebuffer, both in bytes
+    BufferTooSmall { len: usize, min_required: usize },
+    /// The context is a 16bpp one, which already draws through its own back buffer
+    #[cfg(feature = "fb16bpp")]
+    Fb16,
+}
+
+impl fmt::Display for DoubleBufferError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidContext => f.write_str("context is invalid"),
+            Self::NullBuffer => f.write_str("back buffer pointer is null"),
+            Self::BufferTooSmall { len, min_required } => {
+                write!(
+                    f,
+                    "back buffer of {len} bytes is below the minimum of {min_required}"
+                )
+            }
+            #[cfg(feature = "fb16bpp")]
+            Self::Fb16 => f.write_str("16bpp contexts are always double buffered"),
+        }
+    }
+}
+
+impl core::error::Error for DoubleBufferError {}
+
+/// Reasons [`register_global_context`](crate::register_global_context) can fail
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RegistryError {
+    /// A context is already registered under the name
+    AlreadyRegistered,
+    /// All [`MAX_NAMED_CONTEXTS`](crate::MAX_NAMED_CONTEXTS) slots are taken
+    RegistryFull,
+}
+
+impl fmt::Display for RegistryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::AlreadyRegistered => {
+                f.write_str("a context with this name is already registered")
+            }
+            Self::RegistryFull => f.write_str("context registry is full"),
+        }
+    }
+}
+
+impl core::error::Error for RegistryError {}
@@ -0,0 +1,90 @@
+use crate::FlantermContext;
+use crate::double_buffer::{DoubleBuffer, Front};
+use core::cell::UnsafeCell;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+/// Widest framebuffer [`FlantermContext::new_fb16`] accepts, in pixels
+pub const FB16_MAX_WIDTH: usize = 1920;
+/// Tallest framebuffer [`FlantermContext::new_fb16`] accepts, in pixels
+pub const FB16_MAX_HEIGHT: usize = 1200;
+
+/// The 32bpp buffer flanterm renders into for a 16bpp context
+struct Shadow(UnsafeCell<[u32; FB16_MAX_WIDTH * FB16_MAX_HEIGHT]>);
+
+// Only handed out while SHADOW_IN_USE is held
+unsafe impl Sync for Shadow {}
+
+static SHADOW: Shadow = Shadow(UnsafeCell::new([0; FB16_MAX_WIDTH * FB16_MAX_HEIGHT]));
+static SHADOW_IN_USE: AtomicBool = AtomicBool::new(false);
+
+/// Convert a 0x00RRGGBB pixel to RGB565
+pub(crate) fn to_rgb565(pixel: u32) -> u16 {
+    let r = (pixel >> 19) & 0x1f;
+    let g = (pixel >> 10) & 0x3f;
+    let b = (pixel >> 3) & 0x1f;
+    (r << 11 | g << 5 | b) as u16
+}
+
+impl FlantermContext {
+    /// Create a context for a 16bpp RGB565 framebuffer
+    ///
+    /// flanterm only draws 32-bit pixels, so it renders into a static
+    /// RGB888 shadow buffer and the wrapper converts whatever changed to
+    /// RGB565 when it copies it to `framebuffer`. The context is
+    /// [double buffered](Self::enable_double_buffer) through that shadow:
+    /// output reaches the screen on [`flush`](Self::flush), or after every
+    /// write with the default autoflush. Raw pixel values passed to
+    /// [`put_pixel`](Self::put_pixel) and friends are RGB888.
+    ///
+    /// The shadow buffer takes 9 MiB of static memory, so this lives behind
+    /// the `fb16bpp` feature. There is only one, so only one 16bpp context
+    /// can exist at a time. Returns `None` if one already does, the pointer
+    /// is null, the pitch is below `width * 2` or the framebuffer is larger
+    /// than [`FB16_MAX_WIDTH`] x [`FB16_MAX_HEIGHT`].
+    pub fn new_fb16(
+        framebuffer: *mut u16,
+        width: usize,
+        height: usize,
+        pitch: usize,
+    ) -> Option<Self> {
+        if framebuffer.is_null()
+            || width == 0
+            || height == 0
+            || width > FB16_MAX_WIDTH
+            || height > FB16_MAX_HEIGHT
+            || pitch < width * 2
+        {
+            return None;
+        }
+        if SHADOW_IN_USE.swap(true, Ordering::Acquire) {
+            return None;
+        }
+        let shadow = SHADOW.0.get() as *mut u32;
+        let Some(mut ctx) = Self::new_fb_rgb(shadow, width, height, width * 4) else {
+            SHADOW_IN_USE.store(false, Ordering::Release);
+            return None;
+        };
+
+        ctx.take_over_flushing();
+        ctx.double_buffer = Some(DoubleBuffer {
+            front: Front::Rgb565 { framebuffer, pitch },
+            back: shadow,
+            dirty: None,
+        });
+        // flanterm has already painted the shadow once
+        ctx.mark_all_dirty();
+        ctx.present();
+        Some(ctx)
+    }
+
+    /// Give the shadow buffer back if this is a 16bpp context
+    pub(crate) fn release_shadow(&mut self) {
+        if let Some(DoubleBuffer {
+            front: Front::Rgb565 { .. },
+            ..
+        }) = self.double_buffer.take()
+        {
+            SHADOW_IN_USE.store(false, Ordering::Release);
+        }
+    }
+}
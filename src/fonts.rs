@@ -0,0 +1,457 @@
+//! PC Screen Font (PSF) loading
+//!
+//! PSF is the Linux console font format. Both versions store a run of
+//! fixed-size bitmap glyphs, one bit per pixel with the leftmost pixel in
+//! the high bit and each row padded to whole bytes, optionally followed by
+//! a table mapping Unicode characters to glyphs.
+//!
+//! flanterm looks glyphs up by their CP437 code, so only fonts whose first
+//! 256 glyphs are in CP437 order, which includes most PC and VGA fonts,
+//! draw the right characters.
+
+use core::str;
+
+const PSF1_MAGIC: [u8; 2] = [0x36, 0x04];
+const PSF1_MODE_512: u8 = 0x01;
+const PSF1_MODE_HASTAB: u8 = 0x02;
+const PSF1_MODE_SEQ: u8 = 0x04;
+
+const PSF2_MAGIC: [u8; 4] = [0x72, 0xb5, 0x4a, 0x86];
+const PSF2_HAS_UNICODE_TABLE: u32 = 0x01;
+const PSF2_HEADER_SIZE: usize = 32;
+
+/// The Unicode table following the glyphs, in the encoding of its PSF version
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum UnicodeTable {
+    /// Little-endian UCS-2 entries, each glyph's list ended by 0xFFFF
+    Psf1(&'static [u8]),
+    /// UTF-8 entries, each glyph's list ended by 0xFF
+    Psf2(&'static [u8]),
+}
+
+/// A bitmap font loaded from PSF data
+#[derive(Debug, Clone, Copy)]
+pub struct PsfFont {
+    glyphs: &'static [u8],
+    width: usize,
+    height: usize,
+    glyph_count: usize,
+    bytes_per_glyph: usize,
+    pub(crate) table: Option<UnicodeTable>,
+    /// The table sorted by codepoint, once [built](PsfFont::with_unicode_map)
+    unicode_map: Option<&'static [(u32, u16)]>,
+}
+
+impl PsfFont {
+    /// Glyph width in pixels
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Glyph height in pixels
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Number of glyphs in the font
+    pub fn glyph_count(&self) -> usize {
+        self.glyph_count
+    }
+
+    /// Size of one glyph in bytes
+    pub fn bytes_per_glyph(&self) -> usize {
+        self.bytes_per_glyph
+    }
+
+    /// The bitmaps of all glyphs, back to back
+    pub fn glyph_data(&self) -> &'static [u8] {
+        self.glyphs
+    }
+
+    /// The bitmap of the glyph at `index`
+    pub fn glyph(&self, index: usize) -> Option<&'static [u8]> {
+        let start = index.checked_mul(self.bytes_per_glyph)?;
+        self.glyphs
+            .get(start..start.checked_add(self.bytes_per_glyph)?)
+    }
+
+    /// The raw table mapping Unicode characters to glyphs, if the font has one
+    pub fn unicode_table(&self) -> Option<&'static [u8]> {
+        match self.table? {
+            UnicodeTable::Psf1(table) | UnicodeTable::Psf2(table) => Some(table),
+        }
+    }
+
+    /// Get the bitmap of the glyph that draws `codepoint`
+    ///
+    /// Looks the codepoint up in the font's Unicode table, with a binary
+    /// search once a [map](Self::with_unicode_map) is built and by walking
+    /// the table otherwise. Entries for sequences of several codepoints are
+    /// ignored. A font without a table is taken to be indexed by codepoint.
+    pub fn glyph_for_codepoint(&self, codepoint: u32) -> Option<&'static [u8]> {
+        let index = match (self.unicode_map, self.table) {
+            (Some(map), _) => {
+                let found = map.binary_search_by_key(&codepoint, |&(cp, _)| cp).ok()?;
+                map[found].1 as usize
+            }
+            (None, Some(table)) => {
+                UnicodeEntries::new(table)
+                    .find(|&(cp, _)| cp == codepoint)?
+                    .1 as usize
+            }
+            (None, None) => codepoint as usize,
+        };
+        self.glyph(index)
+    }
+
+    /// Check whether the font has a glyph for `codepoint`
+    pub fn has_codepoint(&self, codepoint: u32) -> bool {
+        self.glyph_for_codepoint(codepoint).is_some()
+    }
+
+    /// Number of single codepoint entries in the Unicode table
+    ///
+    /// This is how many slots [`with_unicode_map`](Self::with_unicode_map) needs.
+    pub fn unicode_entry_count(&self) -> usize {
+        self.table
+            .map_or(0, |table| UnicodeEntries::new(table).count())
+    }
+
+    /// Sort the Unicode table into `storage` so lookups can binary search it
+    ///
+    /// Returns `None` if the font has no table or `storage` is shorter than
+    /// [`unicode_entry_count`](Self::unicode_entry_count). Codepoints listed
+    /// for more than one glyph keep one of them.
+    pub fn with_unicode_map(mut self, storage: &'static mut [(u32, u16)]) -> Option<Self> {
+        let table = self.table?;
+        let len = self.unicode_entry_count();
+        let map = storage.get_mut(..len)?;
+        for (slot, entry) in map.iter_mut().zip(UnicodeEntries::new(table)) {
+            *slot = entry;
+        }
+        map.sort_unstable_by_key(|&(cp, _)| cp);
+        self.unicode_map = Some(map);
+        Some(self)
+    }
+
+    /// Check whether flanterm can draw with this font
+    ///
+    /// flanterm needs at least 256 glyphs of one byte per row, so glyphs
+    /// can be at most 8 pixels wide.
+    pub fn is_flanterm_compatible(&self) -> bool {
+        self.glyph_count >= 256 && self.width <= 8
+    }
+}
+
+/// Parse a PSF1 font, returning `None` if the data is not one
+///
+/// Usable in constants, so a font embedded with `include_bytes!` can be
+/// checked at compile time.
+pub const fn load_psf1(data: &'static [u8]) -> Option<PsfFont> {
+    if data.len() < 4 || data[0] != PSF1_MAGIC[0] || data[1] != PSF1_MAGIC[1] {
+        return None;
+    }
+    let mode = data[2];
+    let height = data[3] as usize;
+    if height == 0 {
+        return None;
+    }
+    let glyph_count = if mode & PSF1_MODE_512 != 0 { 512 } else { 256 };
+    let end = 4 + glyph_count * height;
+    if data.len() < end {
+        return None;
+    }
+    let (head, table) = data.split_at(end);
+    let (_, glyphs) = head.split_at(4);
+    let table = if mode & (PSF1_MODE_HASTAB | PSF1_MODE_SEQ) != 0 {
+        Some(UnicodeTable::Psf1(table))
+    } else {
+        None
+    };
+    Some(PsfFont {
+        glyphs,
+        width: 8,
+        height,
+        glyph_count,
+        bytes_per_glyph: height,
+        table,
+        unicode_map: None,
+    })
+}
+
+/// Parse a PSF2 font, returning `None` if the data is not one or is inconsistent
+pub fn load_psf2(data: &'static [u8]) -> Option<PsfFont> {
+    if data.get(..4)? != PSF2_MAGIC {
+        return None;
+    }
+    let field = |i: usize| -> Option<usize> {
+        let bytes = data.get(4 + i * 4..8 + i * 4)?;
+        Some(u32::from_le_bytes(bytes.try_into().ok()?) as usize)
+    };
+    let header_size = field(1)?;
+    let flags = field(2)? as u32;
+    let glyph_count = field(3)?;
+    let bytes_per_glyph = field(4)?;
+    let height = field(5)?;
+    let width = field(6)?;
+    // The glyphs may not overlap the eight header fields
+    if header_size < PSF2_HEADER_SIZE || width == 0 || height == 0 {
+        return None;
+    }
+    if bytes_per_glyph != height.checked_mul(width.div_ceil(8))? {
+        return None;
+    }
+    let end = header_size.checked_add(glyph_count.checked_mul(bytes_per_glyph)?)?;
+    let glyphs = data.get(header_size..end)?;
+    let table = (flags & PSF2_HAS_UNICODE_TABLE != 0).then(|| UnicodeTable::Psf2(&data[end..]));
+    Some(PsfFont {
+        glyphs,
+        width,
+        height,
+        glyph_count,
+        bytes_per_glyph,
+        table,
+        unicode_map: None,
+    })
+}
+
+/// One element of a Unicode table
+enum TableItem {
+    Codepoint(u32),
+    /// Ends the list of the current glyph
+    EndOfGlyph,
+    /// Starts a sequence of codepoints drawn as the current glyph
+    StartOfSequence,
+}
+
+/// Iterator over the (codepoint, glyph) pairs of a Unicode table
+///
+/// Sequences of several codepoints are skipped, as is anything after
+/// malformed data.
+struct UnicodeEntries {
+    table: UnicodeTable,
+    pos: usize,
+    glyph: u16,
+    /// Inside a sequence, which runs to the end of the glyph's list
+    in_sequence: bool,
+}
+
+impl UnicodeEntries {
+    fn new(table: UnicodeTable) -> Self {
+        Self {
+            table,
+            pos: 0,
+            glyph: 0,
+            in_sequence: false,
+        }
+    }
+
+    /// The next codepoint or marker in the table
+    fn next_item(&mut self) -> Option<TableItem> {
+        match self.table {
+            UnicodeTable::Psf1(table) => {
+                let bytes = table.get(self.pos..self.pos + 2)?;
+                self.pos += 2;
+                Some(match u16::from_le_bytes([bytes[0], bytes[1]]) {
+                    0xffff => TableItem::EndOfGlyph,
+                    0xfffe => TableItem::StartOfSequence,
+                    cp => TableItem::Codepoint(cp as u32),
+                })
+            }
+            UnicodeTable::Psf2(table) => {
+                let lead = *table.get(self.pos)?;
+                let len = match lead {
+                    0xff => {
+                        self.pos += 1;
+                        return Some(TableItem::EndOfGlyph);
+                    }
+                    0xfe => {
+                        self.pos += 1;
+                        return Some(TableItem::StartOfSequence);
+                    }
+                    0x00..0x80 => 1,
+                    0xc0..0xe0 => 2,
+                    0xe0..0xf0 => 3,
+                    0xf0..0xf8 => 4,
+                    _ => return None,
+                };
+                let bytes = table.get(self.pos..self.pos + len)?;
+                let ch = str::from_utf8(bytes).ok()?.chars().next()?;
+                self.pos += len;
+                Some(TableItem::Codepoint(ch as u32))
+            }
+        }
+    }
+}
+
+impl Iterator for UnicodeEntries {
+    type Item = (u32, u16);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.next_item()? {
+                TableItem::EndOfGlyph => {
+                    self.glyph = self.glyph.checked_add(1)?;
+                    self.in_sequence = false;
+                }
+                TableItem::StartOfSequence => self.in_sequence = true,
+                TableItem::Codepoint(cp) if !self.in_sequence => return Some((cp, self.glyph)),
+                TableItem::Codepoint(_) => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use std::vec;
+    use std::vec::Vec;
+
+    fn leak(data: Vec<u8>) -> &'static [u8] {
+        data.leak()
+    }
+
+    /// A PSF1 font of 8x`height` glyphs whose rows all hold the glyph index
+    fn psf1(mode: u8, height: u8, table: &[u16]) -> Vec<u8> {
+        let count = if mode & PSF1_MODE_512 != 0 { 512 } else { 256 };
+        let mut data = vec![PSF1_MAGIC[0], PSF1_MAGIC[1], mode, height];
+        for glyph in 0..count {
+            data.extend(core::iter::repeat_n(glyph as u8, height as usize));
+        }
+        for entry in table {
+            data.extend_from_slice(&entry.to_le_bytes());
+        }
+        data
+    }
+
+    /// A PSF2 font of `count` `width`x`height` glyphs whose bytes all hold the glyph index
+    fn psf2(width: u32, height: u32, count: u32, table: Option<&[u8]>) -> Vec<u8> {
+        let bytes_per_glyph = height * width.div_ceil(8);
+        let flags = table.map_or(0, |_| PSF2_HAS_UNICODE_TABLE);
+        let mut data = Vec::from(PSF2_MAGIC);
+        for field in [0, 32, flags, count, bytes_per_glyph, height, width] {
+            data.extend_from_slice(&field.to_le_bytes());
+        }
+        for glyph in 0..count {
+            data.extend(core::iter::repeat_n(glyph as u8, bytes_per_glyph as usize));
+        }
+        data.extend_from_slice(table.unwrap_or_default());
+        data
+    }
+
+    #[test]
+    fn psf1_glyphs() {
+        let font = load_psf1(leak(psf1(0, 16, &[]))).unwrap();
+        assert_eq!((font.width(), font.height()), (8, 16));
+        assert_eq!(font.glyph_count(), 256);
+        assert_eq!(font.bytes_per_glyph(), 16);
+        assert_eq!(font.glyph(65), Some(&[65; 16][..]));
+        assert_eq!(font.glyph(256), None);
+        assert!(font.unicode_table().is_none());
+        assert!(font.is_flanterm_compatible());
+
+        let font = load_psf1(leak(psf1(PSF1_MODE_512, 8, &[]))).unwrap();
+        assert_eq!(font.glyph_count(), 512);
+    }
+
+    #[test]
+    fn psf1_rejects_bad_data() {
+        assert!(load_psf1(b"").is_none());
+        assert!(load_psf1(b"\x36\x05\x00\x10").is_none());
+        assert!(load_psf1(leak(psf1(0, 0, &[]))).is_none());
+
+        let mut truncated = psf1(0, 16, &[]);
+        truncated.pop();
+        assert!(load_psf1(leak(truncated)).is_none());
+    }
+
+    #[test]
+    fn psf1_unicode_table() {
+        // Glyph 0 draws 'A' and the sequence "e" + U+0301, glyph 1 draws 'B' and 'b'
+        let mut table = vec![0x41, 0xfffe, 0x65, 0x301, 0xffff, 0x42, 0x62, 0xffff];
+        table.extend(core::iter::repeat_n(0xffff, 254));
+        let font = load_psf1(leak(psf1(PSF1_MODE_HASTAB, 8, &table))).unwrap();
+
+        assert_eq!(font.unicode_entry_count(), 3);
+        assert_eq!(font.glyph_for_codepoint('A' as u32), Some(&[0; 8][..]));
+        assert_eq!(font.glyph_for_codepoint('b' as u32), Some(&[1; 8][..]));
+        // Codepoints only listed in a sequence have no glyph of their own
+        assert!(!font.has_codepoint('e' as u32));
+        assert!(!font.has_codepoint(0x301));
+        assert!(!font.has_codepoint('C' as u32));
+    }
+
+    #[test]
+    fn psf2_glyphs() {
+        let font = load_psf2(leak(psf2(12, 20, 300, None))).unwrap();
+        assert_eq!((font.width(), font.height()), (12, 20));
+        assert_eq!(font.glyph_count(), 300);
+        assert_eq!(font.bytes_per_glyph(), 40);
+        assert_eq!(font.glyph(299), Some(&[43; 40][..]));
+        assert_eq!(font.glyph(300), None);
+        assert!(!font.is_flanterm_compatible());
+        // Without a table glyphs are indexed by codepoint
+        assert_eq!(font.glyph_for_codepoint(2), Some(&[2; 40][..]));
+    }
+
+    #[test]
+    fn psf2_rejects_bad_data() {
+        assert!(load_psf2(b"\x72\xb5\x4a").is_none());
+        assert!(load_psf2(leak(psf2(0, 16, 256, None))).is_none());
+
+        let mut truncated = psf2(8, 16, 256, None);
+        truncated.pop();
+        assert!(load_psf2(leak(truncated)).is_none());
+
+        // bytes_per_glyph disagrees with the glyph size
+        let mut data = psf2(8, 16, 256, None);
+        data[20..24].copy_from_slice(&15u32.to_le_bytes());
+        assert!(load_psf2(leak(data)).is_none());
+
+        // header_size puts the glyphs on top of the header
+        let mut data = psf2(8, 16, 256, None);
+        data[8..12].copy_from_slice(&0u32.to_le_bytes());
+        assert!(load_psf2(leak(data)).is_none());
+    }
+
+    #[test]
+    fn glyph_index_overflow() {
+        let font = load_psf2(leak(psf2(8, 1, 1, None))).unwrap();
+        assert_eq!(font.glyph(usize::MAX), None);
+        assert_eq!(font.glyph(usize::MAX / 2), None);
+    }
+
+    #[test]
+    fn psf2_unicode_table_and_map() {
+        // Glyph 0 draws 'é' and the sequence "e" + U+0301, glyph 1 draws '€' and 'ÿ'
+        let mut table = Vec::new();
+        table.extend_from_slice("é".as_bytes());
+        table.push(0xfe);
+        table.extend_from_slice("e\u{301}".as_bytes());
+        table.push(0xff);
+        table.extend_from_slice("€ÿ".as_bytes());
+        table.push(0xff);
+        let font = load_psf2(leak(psf2(8, 8, 256, Some(&table)))).unwrap();
+
+        assert_eq!(font.unicode_entry_count(), 3);
+        assert_eq!(font.glyph_for_codepoint('é' as u32), Some(&[0; 8][..]));
+        assert_eq!(font.glyph_for_codepoint('ÿ' as u32), Some(&[1; 8][..]));
+        assert!(!font.has_codepoint('e' as u32));
+
+        assert!(font.with_unicode_map(vec![(0, 0); 2].leak()).is_none());
+        let mapped = font.with_unicode_map(vec![(0, 0); 3].leak()).unwrap();
+        assert_eq!(mapped.glyph_for_codepoint('€' as u32), Some(&[1; 8][..]));
+        assert_eq!(mapped.glyph_for_codepoint('é' as u32), Some(&[0; 8][..]));
+        assert!(!mapped.has_codepoint('x' as u32));
+    }
+
+    #[test]
+    fn malformed_table_ends_the_entries() {
+        let table = [b'a', 0xff, 0x80, b'b', 0xff];
+        let font = load_psf2(leak(psf2(8, 8, 256, Some(&table)))).unwrap();
+        assert_eq!(font.unicode_entry_count(), 1);
+    }
+}
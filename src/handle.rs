@@ -0,0 +1,69 @@
+use crate::bindings::*;
+use core::fmt::{self, Write};
+
+/// A non-owning reference to a flanterm context
+///
+/// Unlike [`FlantermContext`](crate::FlantermContext), dropping a handle
+/// never deinitializes the underlying context. Handles carry no lifetime so
+/// they can be stashed for interrupt handlers and callbacks; the creator
+/// promises they are not used once the context is gone. A handle writes
+/// straight to flanterm and ignores
+/// [`FlantermContext::set_enabled`](crate::FlantermContext::set_enabled).
+pub struct FlantermHandle(pub(crate) *mut flanterm_context);
+
+// Only Send, not Sync - like FlantermContext, concurrent writes must be serialized by the caller
+unsafe impl Send for FlantermHandle {}
+
+impl FlantermHandle {
+    /// Wrap a raw flanterm context pointer without taking ownership
+    ///
+    /// # Safety
+    ///
+    /// `ctx` must be null or point to an initialized flanterm context that
+    /// outlives the returned handle.
+    pub unsafe fn from_raw(ctx: *mut flanterm_context) -> Option<Self> {
+        if ctx.is_null() { None } else { Some(Self(ctx)) }
+    }
+
+    /// Write raw bytes to the terminal
+    pub fn write_bytes(&mut self, bytes: &[u8]) {
+        if self.0.is_null() {
+            return;
+        }
+        unsafe {
+            flanterm_write(self.0, bytes.as_ptr() as *const i8, bytes.len());
+        }
+    }
+
+    /// Flush the terminal output
+    pub fn flush(&mut self) {
+        if self.0.is_null() {
+            return;
+        }
+        unsafe {
+            flanterm_flush(self.0);
+        }
+    }
+
+    /// Force a full refresh
+    pub fn full_refresh(&mut self) {
+        if self.0.is_null() {
+            return;
+        }
+        unsafe {
+            flanterm_full_refresh(self.0);
+        }
+    }
+
+    /// Get the raw flanterm context pointer
+    pub fn as_raw(&self) -> *mut flanterm_context {
+        self.0
+    }
+}
+
+impl Write for FlantermHandle {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.write_bytes(s.as_bytes());
+        Ok(())
+    }
+}
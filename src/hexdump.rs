@@ -0,0 +1,48 @@
+use crate::{AnsiColor, FlantermContext};
+use core::fmt::Write;
+
+impl FlantermContext {
+    /// Print `data` as a hex dump under the heading `label`
+    ///
+    /// Each line holds 16 bytes: the offset in cyan, the bytes in hex in
+    /// white with a gap after the eighth, then the bytes as ASCII with
+    /// anything unprintable shown as a gray `.`. Nothing is printed for an
+    /// empty `label`. The attributes in effect before are restored at the
+    /// end.
+    pub fn hex_dump(&mut self, label: &str, data: &[u8]) {
+        if !label.is_empty() {
+            let _ = writeln!(self, "{label}");
+        }
+        self.push_attrs();
+        for (i, line) in data.chunks(16).enumerate() {
+            self.set_ansi_fg(AnsiColor::Cyan);
+            let _ = write!(self, "{:04x}", i * 16);
+            self.set_ansi_fg(AnsiColor::White);
+            for j in 0..16 {
+                if j == 8 {
+                    let _ = self.write_str(" ");
+                }
+                let _ = match line.get(j) {
+                    Some(byte) => write!(self, " {byte:02x}"),
+                    None => self.write_str("   "),
+                };
+            }
+            let _ = self.write_str("  ");
+            let mut gray = false;
+            for &byte in line {
+                let printable = byte == b' ' || byte.is_ascii_graphic();
+                if printable == gray {
+                    gray = !printable;
+                    self.set_ansi_fg(if gray {
+                        AnsiColor::BrightBlack
+                    } else {
+                        AnsiColor::White
+                    });
+                }
+                let _ = self.write_char(if printable { byte as char } else { '.' });
+            }
+            let _ = self.write_str("\n");
+        }
+        self.pop_attrs();
+    }
+}
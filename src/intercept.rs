@@ -0,0 +1,257 @@
+use crate::FlantermContext;
+
+/// Longest escape sequence offered to an [`EscapeInterceptor`]; longer ones go straight to flanterm
+pub const ESCAPE_MAX_LEN: usize = 256;
+
+/// Hook that sees escape sequences before flanterm does
+///
+/// Used for private extensions to the control protocol: a sequence the
+/// interceptor consumes never reaches the terminal.
+pub trait EscapeInterceptor: Send {
+    /// Called with a complete sequence, starting with ESC and including its terminator
+    ///
+    /// Return `true` if the sequence was handled and must not be passed on
+    /// to flanterm.
+    fn on_escape(&mut self, seq: &[u8]) -> bool;
+}
+
+impl FlantermContext {
+    /// Offer every escape sequence written through this context to `interceptor`
+    ///
+    /// Recognized sequences are CSI, OSC, DCS, SOS, PM and APC strings and
+    /// plain `ESC` sequences with optional intermediates. A sequence split
+    /// across several writes is held back until it is complete. Sequences
+    /// that are cut short by an unexpected byte or grow past
+    /// [`ESCAPE_MAX_LEN`] are passed to flanterm without being offered.
+    /// Consumed sequences are not seen by the OSC handler either.
+    pub fn set_interceptor(&mut self, interceptor: &'static mut dyn EscapeInterceptor) {
+        self.interceptor = Some(EscapeScanner::new(interceptor));
+    }
+
+    /// Remove the interceptor, passing on any sequence it was holding back
+    pub fn clear_interceptor(&mut self) {
+        if let Some(scanner) = self.interceptor.take() {
+            self.write_bytes(&scanner.buf[..scanner.len]);
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ScanState {
+    Ground,
+    /// Saw ESC
+    Escape,
+    /// In an `ESC` sequence after one or more intermediate bytes
+    Intermediate,
+    Csi,
+    /// Inside an OSC, DCS, SOS, PM or APC string
+    String,
+    /// Saw ESC inside a string, a `\\` completes the ST terminator
+    StringEscape,
+}
+
+enum Step {
+    /// Keep the byte and carry on
+    Continue,
+    /// The byte completes the sequence
+    Complete,
+    /// The byte is not part of a sequence
+    Abort,
+}
+
+/// Splits terminal output into plain runs and escape sequences for an interceptor
+pub(crate) struct EscapeScanner {
+    interceptor: &'static mut dyn EscapeInterceptor,
+    state: ScanState,
+    buf: [u8; ESCAPE_MAX_LEN],
+    len: usize,
+    /// The current sequence outgrew `buf` and is being passed through
+    overflowed: bool,
+}
+
+impl EscapeScanner {
+    fn new(interceptor: &'static mut dyn EscapeInterceptor) -> Self {
+        Self {
+            interceptor,
+            state: ScanState::Ground,
+            buf: [0; ESCAPE_MAX_LEN],
+            len: 0,
+            overflowed: false,
+        }
+    }
+
+    /// Scan `bytes`, handing everything the interceptor does not consume to `out` in order
+    pub(crate) fn feed(&mut self, bytes: &[u8], mut out: impl FnMut(&[u8])) {
+        let mut run_start = 0;
+        for (i, &byte) in bytes.iter().enumerate() {
+            if self.state == ScanState::Ground {
+                if byte == 0x1b {
+                    out(&bytes[run_start..i]);
+                    self.start(byte);
+                }
+                continue;
+            }
+
+            match self.step(byte) {
+                Step::Continue => self.push(byte, &mut out),
+                Step::Complete => {
+                    self.push(byte, &mut out);
+                    self.finish(&mut out);
+                    run_start = i + 1;
+                }
+                Step::Abort => {
+                    self.abandon(&mut out);
+                    if byte == 0x1b {
+                        self.start(byte);
+                    } else {
+                        run_start = i;
+                    }
+                }
+            }
+        }
+        if self.state == ScanState::Ground {
+            out(&bytes[run_start..]);
+        }
+    }
+
+    /// Offer a complete sequence that is not going to flanterm
+    pub(crate) fn offer(&mut self, seq: &[u8]) {
+        self.interceptor.on_escape(seq);
+    }
+
+    fn step(&mut self, byte: u8) -> Step {
+        let (next, step) = match (self.state, byte) {
+            (ScanState::Ground, _) => unreachable!(),
+            (ScanState::Escape, b'[') => (ScanState::Csi, Step::Continue),
+            (ScanState::Escape, b']' | b'P' | b'X' | b'^' | b'_') => {
+                (ScanState::String, Step::Continue)
+            }
+            (ScanState::Escape | ScanState::Intermediate, 0x20..=0x2f) => {
+                (ScanState::Intermediate, Step::Continue)
+            }
+            (ScanState::Escape | ScanState::Intermediate, 0x30..=0x7e) => {
+                (ScanState::Ground, Step::Complete)
+            }
+            (ScanState::Csi, 0x20..=0x3f) => (ScanState::Csi, Step::Continue),
+            (ScanState::Csi, 0x40..=0x7e) => (ScanState::Ground, Step::Complete),
+            (ScanState::String, 0x07) => (ScanState::Ground, Step::Complete),
+            (ScanState::String, 0x1b) => (ScanState::StringEscape, Step::Continue),
+            (ScanState::String, _) => (ScanState::String, Step::Continue),
+            (ScanState::StringEscape, b'\\') => (ScanState::Ground, Step::Complete),
+            (ScanState::StringEscape, _) => (ScanState::String, Step::Continue),
+            _ => (ScanState::Ground, Step::Abort),
+        };
+        self.state = next;
+        step
+    }
+
+    fn start(&mut self, esc: u8) {
+        self.state = ScanState::Escape;
+        self.buf[0] = esc;
+        self.len = 1;
+        self.overflowed = false;
+    }
+
+    fn push(&mut self, byte: u8, out: &mut impl FnMut(&[u8])) {
+        if self.overflowed {
+            out(&[byte]);
+        } else if self.len == ESCAPE_MAX_LEN {
+            out(&self.buf);
+            out(&[byte]);
+            self.len = 0;
+            self.overflowed = true;
+        } else {
+            self.buf[self.len] = byte;
+            self.len += 1;
+        }
+    }
+
+    fn finish(&mut self, out: &mut impl FnMut(&[u8])) {
+        if !self.overflowed && !self.interceptor.on_escape(&self.buf[..self.len]) {
+            out(&self.buf[..self.len]);
+        }
+        self.len = 0;
+    }
+
+    fn abandon(&mut self, out: &mut impl FnMut(&[u8])) {
+        if !self.overflowed {
+            out(&self.buf[..self.len]);
+        }
+        self.len = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use std::boxed::Box;
+    use std::cell::RefCell;
+    use std::vec::Vec;
+
+    std::thread_local! {
+        static OFFERED: RefCell<Vec<Vec<u8>>> = const { RefCell::new(Vec::new()) };
+    }
+
+    /// Records every sequence and consumes the private `CSI ... z` ones
+    struct Recorder;
+
+    impl EscapeInterceptor for Recorder {
+        fn on_escape(&mut self, seq: &[u8]) -> bool {
+            OFFERED.with(|offered| offered.borrow_mut().push(seq.into()));
+            seq.ends_with(b"z")
+        }
+    }
+
+    /// Scan `parts`, returning what is passed on and what was offered to the interceptor
+    fn scan(parts: &[&[u8]]) -> (Vec<u8>, Vec<Vec<u8>>) {
+        let mut scanner = EscapeScanner::new(Box::leak(Box::new(Recorder)));
+        let mut out = Vec::new();
+        for part in parts {
+            scanner.feed(part, |run| out.extend_from_slice(run));
+        }
+        (out, OFFERED.with(|offered| offered.take()))
+    }
+
+    #[test]
+    fn consumed_sequences_are_removed() {
+        let (out, offered) = scan(&[b"a\x1b[1;2zb\x1b[31mc"]);
+        assert_eq!(out, b"ab\x1b[31mc");
+        assert_eq!(offered, [&b"\x1b[1;2z"[..], b"\x1b[31m"]);
+    }
+
+    #[test]
+    fn split_sequence_is_held_back() {
+        let (out, offered) = scan(&[b"a\x1b[", b"5", b"z"]);
+        assert_eq!(out, b"a");
+        assert_eq!(offered, [b"\x1b[5z"]);
+    }
+
+    #[test]
+    fn strings_and_plain_escapes() {
+        let (out, offered) = scan(&[b"\x1b]0;t\x07\x1bPq\x1b\\\x1b(B\x1bc"]);
+        assert_eq!(out, b"\x1b]0;t\x07\x1bPq\x1b\\\x1b(B\x1bc");
+        assert_eq!(
+            offered,
+            [&b"\x1b]0;t\x07"[..], b"\x1bPq\x1b\\", b"\x1b(B", b"\x1bc"]
+        );
+    }
+
+    #[test]
+    fn interrupted_sequence_is_passed_through() {
+        let (out, offered) = scan(&[b"\x1b[1\nx\x1b\x1b[z"]);
+        assert_eq!(out, b"\x1b[1\nx\x1b");
+        assert_eq!(offered, [b"\x1b[z"]);
+    }
+
+    #[test]
+    fn overlong_sequence_is_passed_through() {
+        let mut seq = Vec::from(&b"\x1b["[..]);
+        seq.resize(ESCAPE_MAX_LEN + 10, b'1');
+        seq.push(b'z');
+        let (out, offered) = scan(&[&seq[..100], &seq[100..]]);
+        assert_eq!(out, seq);
+        assert!(offered.is_empty());
+    }
+}
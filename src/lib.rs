@@ -1,20 +1,191 @@
 #![no_std]
 
+mod alt_screen;
+pub mod ansi_parser;
+mod attrs;
+mod batch;
+#[allow(non_upper_case_globals, non_camel_case_types)]
 pub mod bindings;
+#[cfg(any(feature = "limine", feature = "multiboot2"))]
+mod boot;
+mod builder;
+mod capability;
+mod color;
+pub mod colors;
+mod config;
+mod cursor;
+mod dbg;
+mod dec_mode;
+#[cfg(feature = "defmt")]
+mod defmt_logger;
+mod dirty;
+mod double_buffer;
+mod draw;
+#[cfg(feature = "embedded-fonts")]
+pub mod embedded_fonts;
+mod eprint;
+mod error;
+#[cfg(feature = "fb16bpp")]
+mod fb16;
+pub mod fonts;
+mod handle;
+mod hexdump;
+mod intercept;
+#[cfg(feature = "log")]
+mod logger;
+mod osc;
+mod panic;
+#[cfg(feature = "per-cpu")]
+mod per_cpu;
+mod pixel_format;
+mod pixels;
+mod refresh;
+mod registry;
+mod scheme;
+mod screen;
+mod selective;
+mod snapshot;
+mod status;
+mod sync;
+mod text;
+pub mod unicode;
+mod utf8;
+mod viewport;
+mod write_buffer;
+
+pub use attrs::{TextAttributes, UnderlineStyle};
+pub use batch::BatchSegment;
+pub use builder::{FlantermContextBuilder, FreeFn, MallocFn};
+pub use capability::TerminalCapability;
+pub use color::{AnsiColor, Color, ColorPair, ColorSpec};
+pub use config::FlantermConfig;
+pub use cursor::CursorShape;
+pub use dbg::_dbg_terminal;
+pub use dec_mode::DecMode;
+pub use draw::{BoxStyle, ProgressStyle};
+pub use eprint::{_eprint, clear_error_context, set_error_context};
+pub use error::{
+    CursorOutOfBoundsError, DoubleBufferError, FbInitError, InvalidUtf8Error, OutOfBoundsError,
+    RegistryError, ReinitError,
+};
+#[cfg(feature = "fb16bpp")]
+pub use fb16::{FB16_MAX_HEIGHT, FB16_MAX_WIDTH};
+pub use handle::FlantermHandle;
+pub use intercept::{ESCAPE_MAX_LEN, EscapeInterceptor};
+#[cfg(feature = "log")]
+pub use logger::FlantermLogger;
+pub use osc::{OSC_MAX_DATA, OscHandler};
+pub use panic::{_panic_report, PanicDisplay, format_panic};
+#[cfg(feature = "per-cpu")]
+pub use per_cpu::{
+    MAX_CPUS, PerCpuFlantermContexts, register_cpu_context, unregister_cpu_context,
+    with_cpu_context,
+};
+pub use pixel_format::PixelFormat;
+pub use registry::{
+    ContextId, MAX_NAMED_CONTEXTS, register_global_context, unregister_global_context,
+    with_named_context,
+};
+pub use scheme::ColorScheme;
+pub use selective::SELECTIVE_REFRESH_MAX_CELLS;
+pub use snapshot::TerminalSnapshot;
+pub use sync::SyncFlantermContext;
+pub use text::{Align, Padded, padded};
+pub use viewport::Viewport;
 
 use bindings::*;
 use core::fmt::{self, Write};
 use core::mem::MaybeUninit;
 use core::ptr;
+use core::sync::atomic::{AtomicBool, Ordering};
 use spin::mutex::Mutex;
 
+/// flanterm's built-in normal and bright palettes, used when only some entries are overridden
+const DEFAULT_ANSI_COLORS: [[Color; 8]; 2] = [
+    [
+        Color(0x000000),
+        Color(0xaa0000),
+        Color(0x00aa00),
+        Color(0xaa5500),
+        Color(0x0000aa),
+        Color(0xaa00aa),
+        Color(0x00aaaa),
+        Color(0xaaaaaa),
+    ],
+    [
+        Color(0x555555),
+        Color(0xff5555),
+        Color(0x55ff55),
+        Color(0xffff55),
+        Color(0x5555ff),
+        Color(0xff55ff),
+        Color(0x55ffff),
+        Color(0xffffff),
+    ],
+];
+
+/// Maximum nesting of [`FlantermContext::push_attrs`]
+pub const ATTR_STACK_DEPTH: usize = 8;
+
 /// A safe wrapper around the flanterm context
 pub struct FlantermContext {
     ctx: *mut flanterm_context,
+    builder: Option<FlantermContextBuilder>,
+    enabled: bool,
+    /// Attributes set through this wrapper, `None` until the first one is set
+    attrs: Option<TextAttributes>,
+    attr_stack: [Option<TextAttributes>; ATTR_STACK_DEPTH],
+    attr_depth: usize,
+    /// Rows `top..=bottom` set with `set_scroll_region`, `None` for the whole screen
+    scroll_region: Option<(usize, usize)>,
+    origin_mode: bool,
+    auto_wrap: bool,
+    /// Set while the alternate screen is shown
+    main_screen: Option<alt_screen::SavedScreen>,
+    osc: osc::OscParser,
+    interceptor: Option<intercept::EscapeScanner>,
+    utf8: utf8::Utf8Validator,
+    double_buffer: Option<double_buffer::DoubleBuffer>,
+    /// The autoflush setting while the wrapper does the flushing, see `take_over_flushing`
+    managed_autoflush: Option<bool>,
+    dirty_tracking: bool,
+    dirty_rect: Option<dirty::Rect>,
+    refresh_limit: Option<refresh::RefreshLimit>,
+    write_buffer: Option<write_buffer::WriteBuffer>,
+    selective_refresh: Option<selective::CellBitmap>,
+    /// What to draw in place of characters without a glyph, `None` for flanterm's block
+    glyph_fallback: Option<u8>,
 }
 
 impl FlantermContext {
+    fn from_parts(ctx: *mut flanterm_context, builder: Option<FlantermContextBuilder>) -> Self {
+        Self {
+            ctx,
+            builder,
+            enabled: true,
+            attrs: None,
+            attr_stack: [None; ATTR_STACK_DEPTH],
+            attr_depth: 0,
+            scroll_region: None,
+            origin_mode: false,
+            auto_wrap: true,
+            main_screen: None,
+            osc: osc::OscParser::new(None),
+            interceptor: None,
+            utf8: utf8::Utf8Validator::new(),
+            double_buffer: None,
+            managed_autoflush: None,
+            dirty_tracking: false,
+            dirty_rect: None,
+            refresh_limit: None,
+            write_buffer: None,
+            selective_refresh: None,
+            glyph_fallback: None,
+        }
+    }
+
     /// Create a new framebuffer-based flanterm context
+    #[allow(clippy::too_many_arguments)]
     pub fn new_fb(
         framebuffer: *mut u32,
         width: usize,
@@ -26,49 +197,332 @@ impl FlantermContext {
         green_mask_shift: u8,
         blue_mask_size: u8,
         blue_mask_shift: u8,
+    ) -> Result<Self, FbInitError> {
+        FlantermContextBuilder::new(framebuffer, width, height, pitch)
+            .color_masks(
+                red_mask_size,
+                red_mask_shift,
+                green_mask_size,
+                green_mask_shift,
+                blue_mask_size,
+                blue_mask_shift,
+            )
+            .build()
+    }
+
+    /// Create a new framebuffer-based flanterm context, discarding the failure reason
+    #[allow(clippy::too_many_arguments)]
+    pub fn new_fb_opt(
+        framebuffer: *mut u32,
+        width: usize,
+        height: usize,
+        pitch: usize,
+        red_mask_size: u8,
+        red_mask_shift: u8,
+        green_mask_size: u8,
+        green_mask_shift: u8,
+        blue_mask_size: u8,
+        blue_mask_shift: u8,
+    ) -> Option<Self> {
+        Self::new_fb(
+            framebuffer,
+            width,
+            height,
+            pitch,
+            red_mask_size,
+            red_mask_shift,
+            green_mask_size,
+            green_mask_shift,
+            blue_mask_size,
+            blue_mask_shift,
+        )
+        .ok()
+    }
+
+    /// Create a new framebuffer-based flanterm context from a [`PixelFormat`]
+    pub fn new_fb_simple(
+        framebuffer: *mut u32,
+        width: usize,
+        height: usize,
+        pitch: usize,
+        format: PixelFormat,
     ) -> Option<Self> {
-        let ctx = unsafe {
-            flanterm_fb_init(
-                None, // malloc
-                None, // free
-                framebuffer,
-                width,
-                height,
-                pitch,
+        FlantermContextBuilder::new(framebuffer, width, height, pitch)
+            .pixel_format(format)
+            .build()
+            .ok()
+    }
+
+    /// Create a new framebuffer-based flanterm context for a standard RGB888 framebuffer
+    pub fn new_fb_rgb(
+        framebuffer: *mut u32,
+        width: usize,
+        height: usize,
+        pitch: usize,
+    ) -> Option<Self> {
+        Self::new_fb_simple(framebuffer, width, height, pitch, PixelFormat::Rgb888)
+    }
+
+    /// Create a new framebuffer-based flanterm context for a BGR888 framebuffer
+    ///
+    /// Blue is at bits 16-23 and red at 0-7, the order of many BIOS and EFI
+    /// framebuffers on x86. Red and blue showing up swapped with
+    /// [`new_fb_rgb`](Self::new_fb_rgb) means this is the one to use.
+    pub fn new_fb_bgr(
+        framebuffer: *mut u32,
+        width: usize,
+        height: usize,
+        pitch: usize,
+    ) -> Option<Self> {
+        Self::new_fb_simple(framebuffer, width, height, pitch, PixelFormat::Bgr888)
+    }
+
+    /// Create a new framebuffer-based flanterm context backed by a custom allocator
+    ///
+    /// See [`FlantermContextBuilder::allocator`] for how flanterm uses the
+    /// callbacks. Passing a null `malloc` with a non-null `free` (or vice
+    /// versa) is undefined behavior in the C library, so both are required.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new_fb_with_alloc(
+        framebuffer: *mut u32,
+        width: usize,
+        height: usize,
+        pitch: usize,
+        red_mask_size: u8,
+        red_mask_shift: u8,
+        green_mask_size: u8,
+        green_mask_shift: u8,
+        blue_mask_size: u8,
+        blue_mask_shift: u8,
+        malloc: MallocFn,
+        free: FreeFn,
+    ) -> Result<Self, FbInitError> {
+        FlantermContextBuilder::new(framebuffer, width, height, pitch)
+            .color_masks(
                 red_mask_size,
                 red_mask_shift,
                 green_mask_size,
                 green_mask_shift,
                 blue_mask_size,
                 blue_mask_shift,
-                ptr::null_mut(), // canvas
-                ptr::null_mut(), // ansi_colours
-                ptr::null_mut(), // ansi_bright_colours
-                ptr::null_mut(), // default_bg
-                ptr::null_mut(), // default_fg
-                ptr::null_mut(), // default_bg_bright
-                ptr::null_mut(), // default_fg_bright
-                ptr::null_mut(), // font
-                0,               // font_width
-                0,               // font_height
-                1,               // font_spacing
-                1,               // font_scale_x
-                1,               // font_scale_y
-                0,               // margin
             )
-        };
+            .allocator(malloc, free)
+            .build()
+    }
 
+    /// Take ownership of a flanterm context created elsewhere, e.g. by a bootloader
+    ///
+    /// The context is deinitialized when the returned value is dropped.
+    ///
+    /// # Safety
+    ///
+    /// `ctx` must be null or point to an initialized flanterm context that
+    /// nothing else will deinitialize or use while it is owned here.
+    pub unsafe fn from_raw_owned(ctx: *mut flanterm_context) -> Option<Self> {
         if ctx.is_null() {
             None
         } else {
-            Some(Self { ctx })
+            Some(Self::from_parts(ctx, None))
+        }
+    }
+
+    /// Wrap a flanterm context created elsewhere without taking ownership
+    ///
+    /// # Safety
+    ///
+    /// `ctx` must be null or point to an initialized flanterm context that
+    /// outlives the returned handle.
+    pub unsafe fn from_raw_borrowed(ctx: *mut flanterm_context) -> Option<FlantermHandle> {
+        unsafe { FlantermHandle::from_raw(ctx) }
+    }
+
+    /// Create a new framebuffer-based flanterm context from a configuration snapshot
+    pub fn from_config(config: &FlantermConfig) -> Result<Self, FbInitError> {
+        FlantermContextBuilder::from_config(config).build()
+    }
+
+    /// Create a non-owning handle to this context
+    ///
+    /// # Safety
+    ///
+    /// The handle must not be used after this context is dropped or
+    /// re-initialized with [`reinit_fb`](Self::reinit_fb), and must not be
+    /// used concurrently with this context.
+    pub unsafe fn handle(&self) -> FlantermHandle {
+        FlantermHandle(self.ctx)
+    }
+
+    /// Get the parameters this context was built with
+    ///
+    /// Returns `None` for contexts wrapped with [`from_raw_owned`](Self::from_raw_owned).
+    pub fn config(&self) -> Option<FlantermConfig> {
+        self.builder.as_ref().map(|b| b.config)
+    }
+
+    /// Re-initialize the context on a new framebuffer, e.g. after a mode switch
+    ///
+    /// Palette, font, margin and allocator settings carry over; the canvas is
+    /// dropped if the resolution changes since it no longer fits. Screen
+    /// contents are lost, and double buffering, dirty tracking and
+    /// selective refresh are switched off. flanterm's bump allocator cannot host a second
+    /// instance, so this requires a context built with a custom allocator.
+    ///
+    /// If flanterm fails to initialize the new framebuffer, the old context
+    /// has already been torn down and `Err(ReinitError::InitFailed)` is
+    /// returned. The context is then invalid: output is discarded,
+    /// [`get_dimensions`](Self::get_dimensions) reports `(0, 0)` and
+    /// [`is_valid`](Self::is_valid) returns `false` until a later
+    /// `reinit_fb` succeeds. All other errors leave the context untouched.
+    pub fn reinit_fb(
+        &mut self,
+        framebuffer: *mut u32,
+        width: usize,
+        height: usize,
+        pitch: usize,
+    ) -> Result<(), ReinitError> {
+        let mut builder = self.builder.clone().ok_or(ReinitError::NoConfig)?;
+        if builder.malloc.is_none() {
+            return Err(ReinitError::NoAllocator);
+        }
+
+        let cfg = &mut builder.config;
+        if cfg.width != width || cfg.height != height {
+            builder.canvas = ptr::null();
+        }
+        cfg.framebuffer = framebuffer as usize as u64;
+        cfg.width = width;
+        cfg.height = height;
+        cfg.pitch = pitch;
+        builder.validate().map_err(ReinitError::InvalidConfig)?;
+
+        self.discard_main_screen();
+        self.drain_write_buffer();
+        if !self.ctx.is_null() {
+            unsafe {
+                flanterm_deinit(self.ctx);
+            }
+        }
+        self.ctx = builder.init_raw();
+        self.builder = Some(builder);
+        // The fresh instance starts with default attributes and modes
+        self.attrs = Some(TextAttributes::default());
+        self.scroll_region = None;
+        self.origin_mode = false;
+        self.auto_wrap = true;
+        self.double_buffer = None;
+        self.managed_autoflush = None;
+        self.dirty_tracking = false;
+        self.dirty_rect = None;
+        self.selective_refresh = None;
+
+        if self.ctx.is_null() {
+            Err(ReinitError::InitFailed)
+        } else {
+            Ok(())
         }
     }
 
+    /// Change one of the 16 ANSI palette entries
+    ///
+    /// Indices 0-7 are the normal colors and 8-15 the bright ones; larger
+    /// indices are ignored. Text already on screen keeps its color, only
+    /// output selecting the entry afterwards uses the new value. The change
+    /// is kept in [`config`](Self::config) and survives [`reinit_fb`](Self::reinit_fb).
+    pub fn set_ansi_color(&mut self, index: u8, color: Color) {
+        if index >= 16 {
+            return;
+        }
+        if let Some(builder) = self.builder.as_mut() {
+            let cfg = &mut builder.config;
+            let palette = if index < 8 {
+                &mut cfg.ansi_colors
+            } else {
+                &mut cfg.ansi_bright_colors
+            };
+            palette.get_or_insert(DEFAULT_ANSI_COLORS[index as usize / 8])[index as usize % 8] =
+                color;
+        }
+        if !self.ctx.is_null() {
+            self.drain_write_buffer();
+            unsafe {
+                flanterm_rs_fb_set_ansi_colour(self.ctx, index as usize, color.0);
+            }
+        }
+    }
+
+    /// Change the default foreground color
+    ///
+    /// If the current text color is the default it switches immediately;
+    /// otherwise the new default takes effect on the next `\x1b[0m` or
+    /// `\x1b[39m`. Text already on screen keeps its color.
+    pub fn set_default_fg(&mut self, color: Color) {
+        if let Some(builder) = self.builder.as_mut() {
+            builder.config.default_fg = Some(color);
+        }
+        if !self.ctx.is_null() {
+            self.drain_write_buffer();
+            unsafe {
+                flanterm_rs_fb_set_default_colours(
+                    self.ctx,
+                    ptr::null(),
+                    &color.0,
+                    ptr::null(),
+                    ptr::null(),
+                );
+            }
+        }
+    }
+
+    /// Change the default background color
+    ///
+    /// Every cell drawn with the default background picks up the new color;
+    /// this triggers a full refresh to repaint them.
+    pub fn set_default_bg(&mut self, color: Color) {
+        if let Some(builder) = self.builder.as_mut() {
+            builder.config.default_bg = Some(color);
+        }
+        if !self.ctx.is_null() {
+            self.drain_write_buffer();
+            unsafe {
+                flanterm_rs_fb_set_default_colours(
+                    self.ctx,
+                    &color.0,
+                    ptr::null(),
+                    ptr::null(),
+                    ptr::null(),
+                );
+            }
+            self.redraw();
+        }
+    }
+
+    /// Check whether the context has a live flanterm instance behind it
+    pub fn is_valid(&self) -> bool {
+        !self.ctx.is_null()
+    }
+
+    /// Suppress or resume all output without tearing down the context
+    ///
+    /// While disabled, [`write_bytes`](Self::write_bytes), the `Write` impl,
+    /// [`flush`](Self::flush) and [`full_refresh`](Self::full_refresh) do
+    /// nothing and never enter flanterm. Output written in the meantime is
+    /// dropped, not replayed when the context is re-enabled.
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    /// Check whether output is currently enabled
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
     /// Get terminal dimensions (columns, rows)
     pub fn get_dimensions(&self) -> (usize, usize) {
         let mut cols = 0;
         let mut rows = 0;
+        if self.ctx.is_null() {
+            return (cols, rows);
+        }
         unsafe {
             flanterm_get_dimensions(self.ctx, &mut cols, &mut rows);
         }
@@ -77,39 +531,145 @@ impl FlantermContext {
 
     /// Set autoflush behavior
     pub fn set_autoflush(&mut self, enabled: bool) {
+        if let Some(autoflush) = &mut self.managed_autoflush {
+            *autoflush = enabled;
+            return;
+        }
+        if self.ctx.is_null() {
+            return;
+        }
         unsafe {
             flanterm_set_autoflush(self.ctx, enabled);
         }
     }
 
+    /// Check whether output reaches the screen without an explicit [`flush`](Self::flush)
+    pub(crate) fn flushes_on_write(&self) -> bool {
+        let autoflush = match self.managed_autoflush {
+            Some(autoflush) => autoflush,
+            None => !self.ctx.is_null() && unsafe { flanterm_rs_get_autoflush(self.ctx) },
+        };
+        autoflush && self.write_buffer.is_none()
+    }
+
     /// Flush the terminal output
     pub fn flush(&mut self) {
+        if self.ctx.is_null() || !self.enabled {
+            return;
+        }
+        self.drain_write_buffer();
+        if self.managed_autoflush.is_some() {
+            self.tracked_flush();
+            return;
+        }
         unsafe {
             flanterm_flush(self.ctx);
         }
     }
 
     /// Force a full refresh
+    ///
+    /// Dropped if it comes too soon after the last one under
+    /// [`set_max_refresh_rate`](Self::set_max_refresh_rate).
     pub fn full_refresh(&mut self) {
+        if self.take_refresh_slot() {
+            self.redraw();
+        }
+    }
+
+    /// Full refresh that ignores the rate limit, for changes that must reach the screen
+    fn redraw(&mut self) {
+        if self.ctx.is_null() || !self.enabled {
+            return;
+        }
+        self.drain_write_buffer();
         unsafe {
             flanterm_full_refresh(self.ctx);
         }
+        if let Some(map) = &mut self.selective_refresh {
+            map.bits.fill(0);
+        }
+        self.mark_all_dirty();
+        self.present();
     }
 
     /// Write raw bytes to the terminal
+    ///
+    /// Malformed UTF-8 is replaced with U+FFFD, or dropped along with the
+    /// rest of the write in [strict mode](Self::set_strict_utf8).
     pub fn write_bytes(&mut self, bytes: &[u8]) {
-        unsafe {
-            flanterm_write(self.ctx, bytes.as_ptr() as *const i8, bytes.len());
+        let _ = self.try_write_bytes(bytes);
+    }
+
+    /// Pass on bytes known to be valid UTF-8, through the interceptor if one is set
+    fn write_validated(&mut self, bytes: &[u8]) {
+        match &mut self.interceptor {
+            Some(scanner) => {
+                let (ctx, osc, buf) = (self.ctx, &mut self.osc, self.write_buffer.as_ref());
+                let fallback = self.glyph_fallback;
+                scanner.feed(bytes, |run| forward(ctx, osc, buf, fallback, run));
+            }
+            None => forward(
+                self.ctx,
+                &mut self.osc,
+                self.write_buffer.as_ref(),
+                self.glyph_fallback,
+                bytes,
+            ),
+        }
+        // Buffered output is only due on an explicit flush
+        if self.managed_autoflush == Some(true) && self.write_buffer.is_none() {
+            self.tracked_flush();
+        }
+    }
+
+    /// Write a single character
+    pub fn write_char(&mut self, ch: char) -> fmt::Result {
+        let mut buf = [0; 4];
+        self.write_bytes(ch.encode_utf8(&mut buf).as_bytes());
+        Ok(())
+    }
+
+    /// Write `ch` `n` times, e.g. to draw a separator line
+    pub fn write_char_n(&mut self, ch: char, n: usize) -> fmt::Result {
+        let mut encoded = [0; 4];
+        let encoded = ch.encode_utf8(&mut encoded).as_bytes();
+        let per_chunk = 64 / encoded.len();
+        let mut chunk = [0; 64];
+        for i in 0..per_chunk.min(n) {
+            chunk[i * encoded.len()..(i + 1) * encoded.len()].copy_from_slice(encoded);
+        }
+
+        let mut left = n;
+        while left > 0 {
+            let count = left.min(per_chunk);
+            self.write_bytes(&chunk[..count * encoded.len()]);
+            left -= count;
         }
+        Ok(())
     }
 
-    /// Clear the terminal
+    /// Clear the terminal and move the cursor home
+    ///
+    /// Only the screen contents change: attributes, modes and the scroll
+    /// region are kept, and cells are cleared to the current background
+    /// color. Use [`hard_reset`](Self::hard_reset) to reset everything.
     pub fn clear(&mut self) {
-        self.write_str("\x1b[2J\x1b[H").unwrap();
+        self.erase_screen();
+        self.home();
     }
 
     /// Move cursor to position (x, y)
+    ///
+    /// Coordinates past the edge are clamped to the last column and row;
+    /// use [`try_move_cursor`](Self::try_move_cursor) to reject them instead.
+    /// In [origin mode](Self::set_origin_mode) `y` counts from the top of
+    /// the scroll region and is clamped to its bottom.
     pub fn move_cursor(&mut self, x: usize, y: usize) {
+        let cols = self.get_dimensions().0;
+        let (top, height) = self.addressable_rows();
+        let x = x.min(cols.saturating_sub(1));
+        let y = top + y.min(height.saturating_sub(1));
         let _ = write!(self, "\x1b[{};{}H", y + 1, x + 1);
     }
 
@@ -120,14 +680,106 @@ impl FlantermContext {
         } else {
             let _ = write!(self, "\x1b[38;5;{}m", fg);
         }
+        self.track_attrs(|a| {
+            a.fg = Some(ColorSpec::Palette(fg));
+            if let Some(bg) = bg {
+                a.bg = Some(ColorSpec::Palette(bg));
+            }
+        });
+    }
+
+    /// Set the foreground to one of the 16 standard ANSI colors
+    pub fn set_ansi_fg(&mut self, color: AnsiColor) {
+        let _ = write!(self, "\x1b[{}m", color.fg_code());
+        self.track_attrs(|a| a.fg = Some(ColorSpec::Ansi(color)));
+    }
+
+    /// Set the background to one of the 16 standard ANSI colors
+    pub fn set_ansi_bg(&mut self, color: AnsiColor) {
+        let _ = write!(self, "\x1b[{}m", color.bg_code());
+        self.track_attrs(|a| a.bg = Some(ColorSpec::Ansi(color)));
+    }
+
+    /// Set the foreground to a 24-bit color
+    ///
+    /// flanterm handles `38;2` true color natively; the framebuffer backend
+    /// converts the exact value to the pixel format with no palette
+    /// approximation. Only channels narrower than 8 bits lose precision.
+    pub fn set_fg_rgb(&mut self, r: u8, g: u8, b: u8) {
+        let _ = write!(self, "\x1b[38;2;{};{};{}m", r, g, b);
+        self.track_attrs(|a| a.fg = Some(ColorSpec::Rgb(r, g, b)));
+    }
+
+    /// Set the background to a 24-bit color
+    ///
+    /// See [`set_fg_rgb`](Self::set_fg_rgb) for how the color is rendered.
+    pub fn set_bg_rgb(&mut self, r: u8, g: u8, b: u8) {
+        let _ = write!(self, "\x1b[48;2;{};{};{}m", r, g, b);
+        self.track_attrs(|a| a.bg = Some(ColorSpec::Rgb(r, g, b)));
+    }
+
+    /// Set the foreground and optionally the background to 24-bit colors
+    pub fn set_color_rgb(&mut self, fg: (u8, u8, u8), bg: Option<(u8, u8, u8)>) {
+        self.set_fg_rgb(fg.0, fg.1, fg.2);
+        if let Some((r, g, b)) = bg {
+            self.set_bg_rgb(r, g, b);
+        }
     }
 
     /// Reset text formatting
     pub fn reset_format(&mut self) {
         self.write_str("\x1b[0m").unwrap();
+        self.attrs = Some(TextAttributes::default());
+    }
+
+    /// Reset foreground and background to the defaults, keeping other attributes
+    pub fn reset_colors(&mut self) {
+        self.write_bytes(b"\x1b[39;49m");
+        self.track_attrs(|a| {
+            a.fg = None;
+            a.bg = None;
+        });
+    }
+
+    /// Reset bold and dim to normal intensity
+    pub fn reset_bold(&mut self) {
+        self.write_bytes(b"\x1b[22m");
+        self.track_attrs(|a| {
+            a.bold = false;
+            a.dim = false;
+        });
+    }
+
+    /// Reset underline of any style
+    pub fn reset_underline(&mut self) {
+        self.write_bytes(b"\x1b[24m");
+        self.track_attrs(|a| {
+            a.underline = false;
+            a.double_underline = false;
+        });
+    }
+
+    /// Reset blink
+    pub fn reset_blink(&mut self) {
+        self.write_bytes(b"\x1b[25m");
+        self.track_attrs(|a| {
+            a.blink = false;
+            a.rapid_blink = false;
+        });
+    }
+
+    /// Reset reverse video
+    pub fn reset_reverse(&mut self) {
+        self.write_bytes(b"\x1b[27m");
+        self.track_attrs(|a| a.reverse = false);
     }
 
     /// Get a reference to the raw flanterm context pointer (unsafe)
+    ///
+    /// # Safety
+    ///
+    /// The pointer is owned by this context and must not be deinitialized or
+    /// used after the context is dropped.
     pub unsafe fn as_raw(&self) -> *mut flanterm_context {
         self.ctx
     }
@@ -140,13 +792,62 @@ impl Write for FlantermContext {
     }
 }
 
+/// Write to flanterm or the write buffer, showing the bytes to the OSC handler on the way
+fn forward(
+    ctx: *mut flanterm_context,
+    osc: &mut osc::OscParser,
+    buf: Option<&write_buffer::WriteBuffer>,
+    fallback: Option<u8>,
+    bytes: &[u8],
+) {
+    if bytes.is_empty() {
+        return;
+    }
+    if osc.is_active() {
+        osc.feed(bytes);
+    }
+    let emit = |bytes: &[u8]| match buf {
+        Some(buf) => buf.push(ctx, bytes),
+        None => write_buffer::write_raw(ctx, bytes),
+    };
+    let Some(fallback) = fallback.filter(|_| !bytes.is_ascii()) else {
+        emit(bytes);
+        return;
+    };
+    for chunk in bytes.utf8_chunks() {
+        let text = chunk.valid();
+        let mut start = 0;
+        for (i, ch) in text.char_indices() {
+            let width = FlantermContext::char_display_width(ch);
+            if width == 0 || ch.is_control() || unicode::has_glyph(ch) {
+                continue;
+            }
+            emit(&text.as_bytes()[start..i]);
+            emit(&[fallback]);
+            if width == 2 {
+                emit(b" ");
+            }
+            start = i + ch.len_utf8();
+        }
+        emit(&text.as_bytes()[start..]);
+        emit(chunk.invalid());
+    }
+}
+
 impl Drop for FlantermContext {
     fn drop(&mut self) {
+        if let Some(hook) = self.builder.as_ref().and_then(|builder| builder.on_deinit) {
+            hook(self);
+        }
+        self.discard_main_screen();
+        self.drain_write_buffer();
         if !self.ctx.is_null() {
             unsafe {
                 flanterm_deinit(self.ctx);
             }
         }
+        #[cfg(feature = "fb16bpp")]
+        self.release_shadow();
     }
 }
 
@@ -171,11 +872,15 @@ impl GlobalFlantermState {
 /// Global flanterm instance protected by a spin mutex
 static GLOBAL_FLANTERM: Mutex<GlobalFlantermState> = Mutex::new(GlobalFlantermState::new());
 
+/// Mirror of `GlobalFlantermState::initialized`, only written with the mutex held
+static GLOBAL_FLANTERM_INITIALIZED: AtomicBool = AtomicBool::new(false);
+
 /// Initialize the global flanterm instance
 pub fn init_global_flanterm(ctx: FlantermContext) {
     let mut state = GLOBAL_FLANTERM.lock();
     state.ctx.write(ctx);
     state.initialized = true;
+    GLOBAL_FLANTERM_INITIALIZED.store(true, Ordering::Release);
 }
 
 /// Get a mutable reference to the global flanterm instance
@@ -192,6 +897,51 @@ where
     }
 }
 
+/// Move the global flanterm instance out, leaving the global uninitialized
+///
+/// Returns `None` if it was not initialized. Calls to
+/// [`with_global_flanterm`] either finish before the context is taken or
+/// find the global empty. The context can be reconfigured, e.g. with
+/// [`FlantermContext::reinit_fb`] after a mode change, and put back with
+/// [`init_global_flanterm`].
+pub fn take_global_flanterm() -> Option<FlantermContext> {
+    let mut state = GLOBAL_FLANTERM.lock();
+    if state.initialized {
+        state.initialized = false;
+        GLOBAL_FLANTERM_INITIALIZED.store(false, Ordering::Release);
+        Some(unsafe { state.ctx.assume_init_read() })
+    } else {
+        None
+    }
+}
+
+/// Replace the global flanterm instance with `ctx`, returning the old one
+///
+/// Unlike [`take_global_flanterm`] followed by [`init_global_flanterm`],
+/// the global is never empty in between, so no output is lost. Returns
+/// `None` if it was not initialized.
+pub fn swap_global_flanterm(ctx: FlantermContext) -> Option<FlantermContext> {
+    let mut state = GLOBAL_FLANTERM.lock();
+    let old = state
+        .initialized
+        .then(|| unsafe { state.ctx.assume_init_read() });
+    state.ctx.write(ctx);
+    state.initialized = true;
+    GLOBAL_FLANTERM_INITIALIZED.store(true, Ordering::Release);
+    old
+}
+
+/// Check whether the global flanterm instance is set, without taking its lock
+///
+/// Cheap enough for hot paths such as interrupt handlers. The load has
+/// acquire ordering, so once it returns `true` everything done before the
+/// context was installed is visible. The answer can be out of date by the
+/// time it is used; [`with_global_flanterm`] still returns `None` if the
+/// context was taken in the meantime.
+pub fn is_global_flanterm_initialized() -> bool {
+    GLOBAL_FLANTERM_INITIALIZED.load(Ordering::Acquire)
+}
+
 /// Print to the global flanterm instance
 pub fn _print(args: fmt::Arguments) {
     with_global_flanterm(|ctx| {
@@ -217,3 +967,97 @@ macro_rules! println {
         $crate::print!("{}\n", format_args!($($arg)*))
     };
 }
+
+/// Print to the error context, see [`set_error_context`]
+#[macro_export]
+macro_rules! eprint {
+    ($($arg:tt)*) => {
+        $crate::_eprint(format_args!($($arg)*))
+    };
+}
+
+/// Print with newline to the error context, see [`set_error_context`]
+#[macro_export]
+macro_rules! eprintln {
+    () => {
+        $crate::eprint!("\n")
+    };
+    ($($arg:tt)*) => {
+        $crate::eprint!("{}\n", format_args!($($arg)*))
+    };
+}
+
+/// Define the `#[panic_handler]`, showing panics on the global instance
+///
+/// The message and location are printed in bold red under a `KERNEL PANIC`
+/// header and the screen is refreshed, then the handler spins forever or,
+/// with `halt_fn = path`, calls that function in a loop. The global lock
+/// is broken if it is held, since the code holding it will not run again.
+/// A panic while the report is drawn, or on another CPU, goes straight to
+/// the halt loop.
+#[macro_export]
+macro_rules! flanterm_panic_handler {
+    () => {
+        $crate::flanterm_panic_handler!(halt_fn = ::core::hint::spin_loop);
+    };
+    (halt_fn = $halt:path $(,)?) => {
+        #[panic_handler]
+        fn flanterm_panic(info: &::core::panic::PanicInfo) -> ! {
+            $crate::_panic_report(info);
+            loop {
+                $halt();
+            }
+        }
+    };
+}
+
+/// Print a hex dump to the global instance, see [`FlantermContext::hex_dump`]
+#[macro_export]
+macro_rules! hexdump {
+    ($label:expr, $data:expr $(,)?) => {
+        $crate::with_global_flanterm(|ctx| ctx.hex_dump($label, $data))
+    };
+}
+
+/// Print and return the value of an expression, like `std::dbg!`
+///
+/// Each value is printed as `[file:line] expr = {:?}` in yellow on its own
+/// line, to the global instance or, when the first argument is
+/// `ctx: <&mut FlantermContext>`, to that context. Several expressions
+/// are returned as a tuple.
+#[macro_export]
+macro_rules! dbg_terminal {
+    (ctx: $ctx:expr $(,)?) => {
+        $crate::_dbg_terminal(Some(&mut *$ctx), file!(), line!(), None)
+    };
+    (ctx: $ctx:expr, $val:expr $(,)?) => {
+        match $val {
+            tmp => {
+                $crate::_dbg_terminal(
+                    Some(&mut *$ctx),
+                    file!(),
+                    line!(),
+                    Some((stringify!($val), &tmp)),
+                );
+                tmp
+            }
+        }
+    };
+    (ctx: $ctx:expr, $($val:expr),+ $(,)?) => {
+        ($($crate::dbg_terminal!(ctx: $ctx, $val)),+,)
+    };
+    () => {
+        $crate::_dbg_terminal(None, file!(), line!(), None)
+    };
+    ($val:expr $(,)?) => {
+        match $val {
+            tmp => {
+                $crate::_dbg_terminal(None, file!(), line!(), Some((stringify!($val), &tmp)));
+                tmp
+            }
+        }
+    };
+    ($($val:expr),+ $(,)?) => {
+        ($($crate::dbg_terminal!($val)),+,)
+    };
+}
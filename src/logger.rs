@@ -0,0 +1,70 @@
+use crate::{AnsiColor, FlantermContext, with_global_flanterm};
+use core::fmt::Write;
+use log::{Level, LevelFilter, Log, Metadata, Record, SetLoggerError};
+use spin::Once;
+
+/// The logger [`FlantermLogger::install`] hands to the `log` crate
+static LOGGER: Once<FlantermLogger> = Once::new();
+
+/// A [`log`] backend printing to the global flanterm instance
+///
+/// Each record is one line starting with its level, colored by level:
+/// errors in bold red, warnings in yellow, info in white, debug in cyan
+/// and trace in gray. Logging from inside [`with_global_flanterm`] would
+/// wait for the lock it already holds, so it must be avoided there.
+pub struct FlantermLogger {
+    min_level: LevelFilter,
+}
+
+impl FlantermLogger {
+    /// A logger printing records of `min_level` and more severe
+    pub const fn new(min_level: LevelFilter) -> Self {
+        Self { min_level }
+    }
+
+    /// Make a logger for `level` the global `log` backend
+    ///
+    /// Fails if a logger has been set already.
+    pub fn install(level: LevelFilter) -> Result<(), SetLoggerError> {
+        log::set_logger(LOGGER.call_once(|| Self::new(level)))?;
+        log::set_max_level(level);
+        Ok(())
+    }
+}
+
+/// Set the color of records at `level`
+fn apply_level_style(ctx: &mut FlantermContext, level: Level) {
+    match level {
+        Level::Error => {
+            ctx.set_bold();
+            ctx.set_ansi_fg(AnsiColor::Red);
+        }
+        Level::Warn => ctx.set_ansi_fg(AnsiColor::Yellow),
+        Level::Info => ctx.set_ansi_fg(AnsiColor::White),
+        Level::Debug => ctx.set_ansi_fg(AnsiColor::Cyan),
+        Level::Trace => ctx.set_ansi_fg(AnsiColor::BrightBlack),
+    }
+}
+
+impl Log for FlantermLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.min_level
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        with_global_flanterm(|ctx| {
+            ctx.push_attrs();
+            apply_level_style(ctx, record.level());
+            let _ = write!(ctx, "[{:<5}] {}", record.level(), record.args());
+            ctx.pop_attrs();
+            let _ = ctx.write_str("\n");
+        });
+    }
+
+    fn flush(&self) {
+        with_global_flanterm(|ctx| ctx.flush());
+    }
+}
@@ -0,0 +1,256 @@
+use crate::FlantermContext;
+
+/// Callback receiving each complete OSC sequence as its numeric code and the data after the `;`
+pub type OscHandler = unsafe fn(code: u32, data: &[u8]);
+
+/// Longest OSC payload passed to an [`OscHandler`]; longer sequences are dropped
+pub const OSC_MAX_DATA: usize = 256;
+
+impl FlantermContext {
+    /// Set the window title (OSC 0)
+    ///
+    /// flanterm has no title to show and swallows the sequence without
+    /// printing anything, so this is safe to call unconditionally. Control
+    /// characters in `title` are dropped since they would end the sequence
+    /// early and leak the rest onto the screen.
+    pub fn set_title(&mut self, title: &str) {
+        self.write_osc(b"0", title.as_bytes());
+    }
+
+    /// Set the icon name (OSC 1); ignored by flanterm like [`set_title`](Self::set_title)
+    pub fn set_icon_name(&mut self, name: &str) {
+        self.write_osc(b"1", name.as_bytes());
+    }
+
+    /// Start a hyperlink to `uri` (OSC 8)
+    ///
+    /// Text written until [`end_hyperlink`](Self::end_hyperlink) is the link
+    /// text. flanterm drops the sequence and shows the text normally, so
+    /// output stays readable when it is also sent to a terminal emulator
+    /// that renders clickable links.
+    pub fn begin_hyperlink(&mut self, uri: &str) {
+        self.write_bytes(b"\x1b]8;;");
+        self.write_osc_data(uri.as_bytes());
+        self.write_bytes(b"\x07");
+    }
+
+    /// End the hyperlink started by [`begin_hyperlink`](Self::begin_hyperlink)
+    pub fn end_hyperlink(&mut self) {
+        self.write_bytes(b"\x1b]8;;\x07");
+    }
+
+    /// Run `f` with everything it writes linked to `uri`
+    pub fn with_hyperlink(&mut self, uri: &str, f: impl FnOnce(&mut Self)) {
+        self.begin_hyperlink(uri);
+        f(self);
+        self.end_hyperlink();
+    }
+
+    /// Call `handler` for every OSC sequence written through this context
+    ///
+    /// Output is still passed to flanterm unchanged, which discards OSC
+    /// sequences; the handler sees them as they stream past, so sequences
+    /// split across several writes are reassembled. Both the BEL and
+    /// `\x1b\\` terminators are recognized. Sequences whose code is not a
+    /// number, or whose data exceeds [`OSC_MAX_DATA`] bytes, are dropped.
+    /// Writes through a [`FlantermHandle`](crate::FlantermHandle) are not
+    /// seen.
+    ///
+    /// # Safety
+    ///
+    /// `handler` is called from inside `write_bytes`, `write!` and every
+    /// helper that writes, so it must be sound to run at any of those call
+    /// sites, e.g. it must not take a lock held around the write.
+    pub unsafe fn set_osc_handler(&mut self, handler: OscHandler) {
+        self.osc = OscParser::new(Some(handler));
+    }
+
+    /// Stop reporting OSC sequences
+    pub fn clear_osc_handler(&mut self) {
+        self.osc = OscParser::new(None);
+    }
+
+    /// Write `\x1b]{code};{data}\x07`, leaving out control characters in `data`
+    pub(crate) fn write_osc(&mut self, code: &[u8], data: &[u8]) {
+        self.write_bytes(b"\x1b]");
+        self.write_bytes(code);
+        self.write_bytes(b";");
+        self.write_osc_data(data);
+        self.write_bytes(b"\x07");
+    }
+
+    fn write_osc_data(&mut self, data: &[u8]) {
+        for part in data.split(|b| b.is_ascii_control()) {
+            self.write_bytes(part);
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum OscState {
+    Ground,
+    Escape,
+    Code,
+    Data,
+    /// Inside a sequence that will not be reported, waiting for its terminator
+    Skip,
+    /// Saw ESC inside a sequence, a `\\` completes the ST terminator
+    StringEscape {
+        skip: bool,
+    },
+}
+
+/// Streaming scanner that picks OSC sequences out of terminal output
+pub(crate) struct OscParser {
+    handler: Option<OscHandler>,
+    state: OscState,
+    code: u32,
+    data: [u8; OSC_MAX_DATA],
+    len: usize,
+}
+
+impl OscParser {
+    pub(crate) const fn new(handler: Option<OscHandler>) -> Self {
+        Self {
+            handler,
+            state: OscState::Ground,
+            code: 0,
+            data: [0; OSC_MAX_DATA],
+            len: 0,
+        }
+    }
+
+    pub(crate) fn is_active(&self) -> bool {
+        self.handler.is_some()
+    }
+
+    pub(crate) fn feed(&mut self, bytes: &[u8]) {
+        for &b in bytes {
+            self.state = match (self.state, b) {
+                (OscState::Ground, 0x1b) => OscState::Escape,
+                (OscState::Ground, _) => OscState::Ground,
+                (OscState::Escape, b']') => {
+                    self.code = 0;
+                    self.len = 0;
+                    OscState::Code
+                }
+                (OscState::Escape, 0x1b) => OscState::Escape,
+                (OscState::Escape, _) => OscState::Ground,
+                (OscState::Code, b'0'..=b'9') => match self.code.checked_mul(10) {
+                    Some(code) => {
+                        self.code = code + (b - b'0') as u32;
+                        OscState::Code
+                    }
+                    None => OscState::Skip,
+                },
+                (OscState::Code, b';') => OscState::Data,
+                (OscState::Code | OscState::Data, 0x07) => {
+                    self.dispatch();
+                    OscState::Ground
+                }
+                (OscState::Code | OscState::Data, 0x1b) => OscState::StringEscape { skip: false },
+                (OscState::Code, _) => OscState::Skip,
+                (OscState::Data, _) if self.len == OSC_MAX_DATA => OscState::Skip,
+                (OscState::Data, _) => {
+                    self.data[self.len] = b;
+                    self.len += 1;
+                    OscState::Data
+                }
+                (OscState::Skip, 0x07) => OscState::Ground,
+                (OscState::Skip, 0x1b) => OscState::StringEscape { skip: true },
+                (OscState::Skip, _) => OscState::Skip,
+                (OscState::StringEscape { skip }, b'\\') => {
+                    if !skip {
+                        self.dispatch();
+                    }
+                    OscState::Ground
+                }
+                // flanterm ends the OSC on any ESC, so treat this as the start of a new sequence
+                (OscState::StringEscape { .. }, b']') => {
+                    self.code = 0;
+                    self.len = 0;
+                    OscState::Code
+                }
+                (OscState::StringEscape { .. }, _) => OscState::Ground,
+            };
+        }
+    }
+
+    fn dispatch(&self) {
+        if let Some(handler) = self.handler {
+            unsafe {
+                handler(self.code, &self.data[..self.len]);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use std::cell::RefCell;
+    use std::vec::Vec;
+
+    std::thread_local! {
+        static SEEN: RefCell<Vec<(u32, Vec<u8>)>> = const { RefCell::new(Vec::new()) };
+    }
+
+    fn record(code: u32, data: &[u8]) {
+        SEEN.with(|seen| seen.borrow_mut().push((code, data.into())));
+    }
+
+    fn scan(parts: &[&[u8]]) -> Vec<(u32, Vec<u8>)> {
+        let mut parser = OscParser::new(Some(record));
+        for part in parts {
+            parser.feed(part);
+        }
+        SEEN.with(|seen| seen.take())
+    }
+
+    #[test]
+    fn both_terminators() {
+        assert_eq!(
+            scan(&[b"a\x1b]0;title\x07b\x1b]8;;uri\x1b\\c"]),
+            [(0, b"title".into()), (8, b";uri".into())]
+        );
+    }
+
+    #[test]
+    fn split_sequence_is_reassembled() {
+        assert_eq!(
+            scan(&[b"\x1b", b"]5", b"2;da", b"ta\x1b", b"\\"]),
+            [(52, b"data".into())]
+        );
+    }
+
+    #[test]
+    fn sequence_without_data() {
+        assert_eq!(scan(&[b"\x1b]104\x07"]), [(104, b"".into())]);
+    }
+
+    #[test]
+    fn other_sequences_are_ignored() {
+        assert_eq!(scan(&[b"\x1b[31m\x1b[2J\x1bc\x07"]), []);
+    }
+
+    #[test]
+    fn malformed_sequences_are_dropped() {
+        // Non-numeric and overflowing codes
+        assert_eq!(scan(&[b"\x1b]x;a\x07\x1b]99999999999;a\x07"]), []);
+
+        let mut long = Vec::from(&b"\x1b]0;"[..]);
+        long.resize(long.len() + OSC_MAX_DATA + 1, b'a');
+        long.push(0x07);
+        assert_eq!(scan(&[&long]), []);
+    }
+
+    #[test]
+    fn esc_inside_a_sequence_ends_it() {
+        assert_eq!(
+            scan(&[b"\x1b]0;lost\x1b[m\x1b]0;lost\x1b]1;kept\x07"]),
+            [(1, b"kept".into())]
+        );
+    }
+}
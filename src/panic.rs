@@ -0,0 +1,95 @@
+use crate::text::{Align, padded};
+use crate::{AnsiColor, FlantermContext, GLOBAL_FLANTERM};
+use core::fmt::{self, Write};
+use core::panic::PanicInfo;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+const HEADER: &str = "KERNEL PANIC";
+
+/// Width of [`PanicDisplay`] unless the format string gives one
+const PANIC_DISPLAY_WIDTH: usize = 64;
+
+/// Set once [`_panic_report`] starts, so a panic while reporting is not reported again
+static REPORTING: AtomicBool = AtomicBool::new(false);
+
+/// Draw `info` across the width of `ctx`, framed by borders
+///
+/// Below the top border comes a centered `KERNEL PANIC` header in bold red,
+/// then the message in white and the location in dim cyan, with the
+/// attributes in effect before restored afterwards.
+pub fn format_panic(info: &PanicInfo, ctx: &mut FlantermContext) {
+    let width = ctx.get_dimensions().0;
+    ctx.push_attrs();
+    ctx.reset_format();
+    let _ = writeln!(ctx, "{}", padded("", width, Align::Left, '═'));
+    ctx.set_bold();
+    ctx.set_ansi_fg(AnsiColor::Red);
+    let _ = writeln!(ctx, "{}", padded(HEADER, width, Align::Center, ' '));
+    ctx.reset_format();
+    ctx.set_ansi_fg(AnsiColor::White);
+    let _ = writeln!(ctx, "{}", info.message());
+    if let Some(location) = info.location() {
+        ctx.reset_format();
+        ctx.set_dim();
+        ctx.set_ansi_fg(AnsiColor::Cyan);
+        let _ = writeln!(ctx, "at {}:{}", location.file(), location.line());
+    }
+    ctx.reset_format();
+    let _ = writeln!(ctx, "{}", padded("", width, Align::Left, '═'));
+    ctx.pop_attrs();
+}
+
+/// A panic laid out as [`format_panic`] draws it, in plain text
+///
+/// The borders are drawn with `=` and are as wide as the width in the
+/// format string, such as `{:80}`, or 64 columns without one.
+#[derive(Clone, Copy)]
+pub struct PanicDisplay<'a>(pub &'a PanicInfo<'a>);
+
+impl fmt::Display for PanicDisplay<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = f.width().unwrap_or(PANIC_DISPLAY_WIDTH);
+        let border = padded("", width, Align::Left, '=');
+        writeln!(f, "{border}")?;
+        writeln!(f, "{}", padded(HEADER, width, Align::Center, ' '))?;
+        writeln!(f, "{}", self.0.message())?;
+        if let Some(location) = self.0.location() {
+            writeln!(f, "at {}:{}", location.file(), location.line())?;
+        }
+        writeln!(f, "{border}")
+    }
+}
+
+/// Show a panic on the global instance, for [`flanterm_panic_handler!`](crate::flanterm_panic_handler)
+///
+/// Returns right away for any panic after the first, including one raised
+/// while drawing the report, leaving the handler to halt.
+pub fn _panic_report(info: &PanicInfo) {
+    if REPORTING.swap(true, Ordering::Acquire) {
+        return;
+    }
+    // The panicking code may hold the lock, and it never runs again to release it
+    let mut state = match GLOBAL_FLANTERM.try_lock() {
+        Some(state) => state,
+        None => unsafe {
+            GLOBAL_FLANTERM.force_unlock();
+            GLOBAL_FLANTERM.lock()
+        },
+    };
+    if !state.initialized {
+        return;
+    }
+    let ctx = unsafe { state.ctx.assume_init_mut() };
+    ctx.set_enabled(true);
+    ctx.reset_format();
+    ctx.set_bold();
+    ctx.set_ansi_fg(AnsiColor::Red);
+    let _ = write!(ctx, "\n*** {HEADER} ***\n{}\n", info.message());
+    if let Some(location) = info.location() {
+        let _ = writeln!(ctx, "at {}:{}", location.file(), location.line());
+    }
+    ctx.reset_format();
+    ctx.flush();
+    // Not full_refresh, which a refresh rate limit could drop
+    ctx.redraw();
+}
@@ -0,0 +1,76 @@
+use crate::FlantermContext;
+use spin::mutex::Mutex;
+
+/// Number of CPUs [`PerCpuFlantermContexts`] has a slot for
+pub const MAX_CPUS: usize = 16;
+
+/// One context per CPU, each behind its own lock
+///
+/// CPUs only wait for each other when they use the same slot, unlike
+/// with the single [global instance](crate::with_global_flanterm). Each
+/// context should draw to its own region of the framebuffer. Every slot
+/// holds a whole [`FlantermContext`], so a static of this type takes up
+/// `MAX_CPUS` times its size.
+pub struct PerCpuFlantermContexts {
+    slots: [Mutex<Option<FlantermContext>>; MAX_CPUS],
+}
+
+impl PerCpuFlantermContexts {
+    /// A set with every slot empty
+    pub const fn new() -> Self {
+        Self {
+            slots: [const { Mutex::new(None) }; MAX_CPUS],
+        }
+    }
+
+    /// Put `ctx` in the slot of `cpu_id`, returning the context left over
+    ///
+    /// That is the one the slot held before, or `ctx` itself if `cpu_id`
+    /// is not below [`MAX_CPUS`].
+    pub fn register(&self, cpu_id: usize, ctx: FlantermContext) -> Option<FlantermContext> {
+        match self.slots.get(cpu_id) {
+            Some(slot) => slot.lock().replace(ctx),
+            None => Some(ctx),
+        }
+    }
+
+    /// Remove the context of `cpu_id` and hand it back
+    pub fn unregister(&self, cpu_id: usize) -> Option<FlantermContext> {
+        self.slots.get(cpu_id)?.lock().take()
+    }
+
+    /// Run `f` on the context of `cpu_id`, or return `None` if it has none
+    pub fn with<F, R>(&self, cpu_id: usize, f: F) -> Option<R>
+    where
+        F: FnOnce(&mut FlantermContext) -> R,
+    {
+        self.slots.get(cpu_id)?.lock().as_mut().map(f)
+    }
+}
+
+impl Default for PerCpuFlantermContexts {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The per-CPU contexts used by [`register_cpu_context`] and [`with_cpu_context`]
+static PER_CPU_FLANTERM: PerCpuFlantermContexts = PerCpuFlantermContexts::new();
+
+/// Give CPU `cpu_id` a global context of its own, see [`PerCpuFlantermContexts::register`]
+pub fn register_cpu_context(cpu_id: usize, ctx: FlantermContext) -> Option<FlantermContext> {
+    PER_CPU_FLANTERM.register(cpu_id, ctx)
+}
+
+/// Run `f` on the global context of CPU `cpu_id`, or return `None` if it has none
+pub fn with_cpu_context<F, R>(cpu_id: usize, f: F) -> Option<R>
+where
+    F: FnOnce(&mut FlantermContext) -> R,
+{
+    PER_CPU_FLANTERM.with(cpu_id, f)
+}
+
+/// Remove the global context of CPU `cpu_id` and hand it back
+pub fn unregister_cpu_context(cpu_id: usize) -> Option<FlantermContext> {
+    PER_CPU_FLANTERM.unregister(cpu_id)
+}
@@ -0,0 +1,83 @@
+/// Layout of the color channels within a 32-bit framebuffer pixel
+///
+/// flanterm always writes whole 32-bit words, so the `X` variants describe
+/// the same channel positions as their 24-bit counterparts; the unused top
+/// byte is simply left as zero.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PixelFormat {
+    /// Red at bits 16-23, green at 8-15, blue at 0-7
+    Rgb888,
+    /// Blue at bits 16-23, green at 8-15, red at 0-7
+    Bgr888,
+    /// Same layout as `Rgb888` with an explicit unused top byte
+    Xrgb8888,
+    /// Same layout as `Bgr888` with an explicit unused top byte
+    Xbgr8888,
+    /// Arbitrary mask sizes and shifts, e.g. 10 bits per channel
+    Custom {
+        red_size: u8,
+        red_shift: u8,
+        green_size: u8,
+        green_shift: u8,
+        blue_size: u8,
+        blue_shift: u8,
+    },
+}
+
+impl PixelFormat {
+    /// Build a format from raw mask values, as reported by a bootloader
+    ///
+    /// Well-known layouts map to their named variant and anything else
+    /// becomes `Custom`.
+    pub const fn from_masks(
+        red_size: u8,
+        red_shift: u8,
+        green_size: u8,
+        green_shift: u8,
+        blue_size: u8,
+        blue_shift: u8,
+    ) -> Self {
+        match (
+            red_size,
+            red_shift,
+            green_size,
+            green_shift,
+            blue_size,
+            blue_shift,
+        ) {
+            (8, 16, 8, 8, 8, 0) => Self::Rgb888,
+            (8, 0, 8, 8, 8, 16) => Self::Bgr888,
+            _ => Self::Custom {
+                red_size,
+                red_shift,
+                green_size,
+                green_shift,
+                blue_size,
+                blue_shift,
+            },
+        }
+    }
+
+    /// Expand to `(red_size, red_shift, green_size, green_shift, blue_size, blue_shift)`
+    pub const fn masks(self) -> (u8, u8, u8, u8, u8, u8) {
+        match self {
+            Self::Rgb888 | Self::Xrgb8888 => (8, 16, 8, 8, 8, 0),
+            Self::Bgr888 | Self::Xbgr8888 => (8, 0, 8, 8, 8, 16),
+            Self::Custom {
+                red_size,
+                red_shift,
+                green_size,
+                green_shift,
+                blue_size,
+                blue_shift,
+            } => (
+                red_size,
+                red_shift,
+                green_size,
+                green_shift,
+                blue_size,
+                blue_shift,
+            ),
+        }
+    }
+}
@@ -0,0 +1,192 @@
+use crate::bindings::*;
+use crate::{FlantermContext, OutOfBoundsError};
+use core::ptr;
+
+/// The framebuffer flanterm draws to
+pub(crate) struct Surface {
+    pub(crate) framebuffer: *mut u32,
+    pub(crate) width: usize,
+    pub(crate) height: usize,
+    /// Length of one scanline in bytes
+    pub(crate) pitch: usize,
+}
+
+impl Surface {
+    /// Pointer to the pixel at (`x`, `y`), which must be in bounds
+    pub(crate) fn pixel_ptr(&self, x: usize, y: usize) -> *mut u32 {
+        unsafe { self.framebuffer.byte_add(y * self.pitch).add(x) }
+    }
+}
+
+impl FlantermContext {
+    /// Get the framebuffer flanterm draws to, or `None` if the context is invalid
+    ///
+    /// With flanterm's bump allocator this is the centered area flanterm
+    /// limits itself to on large screens, not the whole framebuffer.
+    pub(crate) fn surface(&self) -> Option<Surface> {
+        if self.ctx.is_null() {
+            return None;
+        }
+        let mut surface = Surface {
+            framebuffer: ptr::null_mut(),
+            width: 0,
+            height: 0,
+            pitch: 0,
+        };
+        unsafe {
+            flanterm_rs_fb_get_framebuffer(
+                self.ctx,
+                &mut surface.framebuffer,
+                &mut surface.width,
+                &mut surface.height,
+                &mut surface.pitch,
+            );
+        }
+        Some(surface)
+    }
+
+    /// Write one raw pixel value straight into the framebuffer at (`x`, `y`)
+    ///
+    /// `color` is in the framebuffer's own pixel format and coordinates are
+    /// in pixels from the top left of the area flanterm draws to. flanterm
+    /// does not know about the pixel, so it is painted over whenever the
+    /// cell beneath it is redrawn, e.g. by text, scrolling or
+    /// [`full_refresh`](Self::full_refresh). Nothing is written while the
+    /// context is [disabled](Self::set_enabled).
+    pub fn put_pixel(&mut self, x: usize, y: usize, color: u32) -> Result<(), OutOfBoundsError> {
+        let surface = self.surface();
+        let (width, height) = surface.as_ref().map_or((0, 0), |s| (s.width, s.height));
+        if x >= width || y >= height {
+            return Err(OutOfBoundsError {
+                x,
+                y,
+                width,
+                height,
+            });
+        }
+        if let Some(surface) = surface
+            && self.enabled
+        {
+            unsafe { surface.pixel_ptr(x, y).write_volatile(color) };
+            self.mark_dirty((x, y, 1, 1));
+        }
+        Ok(())
+    }
+
+    /// Read the raw pixel value at (`x`, `y`), or `None` if it is outside the framebuffer
+    ///
+    /// This reads back what is on screen, so output flanterm has queued but
+    /// not yet [flushed](Self::flush) is not visible yet. With
+    /// [double buffering](Self::enable_double_buffer) it reads the back buffer.
+    pub fn get_pixel(&self, x: usize, y: usize) -> Option<u32> {
+        let surface = self.surface()?;
+        if x >= surface.width || y >= surface.height {
+            return None;
+        }
+        Some(unsafe { surface.pixel_ptr(x, y).read_volatile() })
+    }
+
+    /// Fill a `w`x`h` pixel rectangle with its top left corner at (`x`, `y`)
+    ///
+    /// Works like [`put_pixel`](Self::put_pixel), except that the parts of
+    /// the rectangle outside the framebuffer are clipped silently.
+    pub fn fill_pixels(&mut self, x: usize, y: usize, w: usize, h: usize, color: u32) {
+        let Some(surface) = self.surface() else {
+            return;
+        };
+        if !self.enabled || x >= surface.width || y >= surface.height {
+            return;
+        }
+        let w = w.min(surface.width - x);
+        let h = h.min(surface.height - y);
+        for row in y..y + h {
+            unsafe { fill_row(surface.pixel_ptr(x, row), w, color) };
+        }
+        self.mark_dirty((x, y, w, h));
+    }
+
+    /// Draw a horizontal line of `len` pixels starting at (`x`, `y`), clipped to the framebuffer
+    pub fn draw_pixel_hline(&mut self, x: usize, y: usize, len: usize, color: u32) {
+        self.fill_pixels(x, y, len, 1, color);
+    }
+
+    /// Draw a vertical line of `len` pixels starting at (`x`, `y`), clipped to the framebuffer
+    pub fn draw_pixel_vline(&mut self, x: usize, y: usize, len: usize, color: u32) {
+        self.fill_pixels(x, y, 1, len, color);
+    }
+
+    /// Fill the whole framebuffer with the raw pixel value `color`
+    ///
+    /// Uses 128-bit stores where the target has them, which makes it much
+    /// faster than [`fill_pixels`](Self::fill_pixels) on large screens. Like
+    /// other pixel writes flanterm does not know about it, so text shows
+    /// up again wherever it is redrawn.
+    pub fn fast_fill(&mut self, color: u32) {
+        let Some(surface) = self.surface() else {
+            return;
+        };
+        if !self.enabled {
+            return;
+        }
+        for row in 0..surface.height {
+            unsafe { fill_row(surface.pixel_ptr(0, row), surface.width, color) };
+        }
+        self.mark_all_dirty();
+    }
+}
+
+/// Set `len` pixels from `line` to `color`, four at a time with SSE2
+#[cfg(all(
+    any(target_arch = "x86", target_arch = "x86_64"),
+    target_feature = "sse2"
+))]
+unsafe fn fill_row(line: *mut u32, len: usize, color: u32) {
+    #[cfg(target_arch = "x86")]
+    use core::arch::x86::*;
+    #[cfg(target_arch = "x86_64")]
+    use core::arch::x86_64::*;
+
+    unsafe {
+        let quad = _mm_set1_epi32(color as i32);
+        let mut i = 0;
+        while i + 4 <= len {
+            _mm_storeu_si128(line.add(i) as *mut __m128i, quad);
+            i += 4;
+        }
+        for i in i..len {
+            line.add(i).write_volatile(color);
+        }
+    }
+}
+
+/// Set `len` pixels from `line` to `color`, four at a time with NEON
+#[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+unsafe fn fill_row(line: *mut u32, len: usize, color: u32) {
+    use core::arch::aarch64::*;
+
+    unsafe {
+        let quad = vdupq_n_u32(color);
+        let mut i = 0;
+        while i + 4 <= len {
+            vst1q_u32(line.add(i), quad);
+            i += 4;
+        }
+        for i in i..len {
+            line.add(i).write_volatile(color);
+        }
+    }
+}
+
+/// Set `len` pixels from `line` to `color`
+#[cfg(not(any(
+    all(
+        any(target_arch = "x86", target_arch = "x86_64"),
+        target_feature = "sse2"
+    ),
+    all(target_arch = "aarch64", target_feature = "neon")
+)))]
+unsafe fn fill_row(line: *mut u32, len: usize, color: u32) {
+    for i in 0..len {
+        unsafe { line.add(i).write_volatile(color) };
+    }
+}
@@ -0,0 +1,44 @@
+use crate::FlantermContext;
+
+/// Minimum spacing of full refreshes, set with `set_max_refresh_rate`
+pub(crate) struct RefreshLimit {
+    interval_us: u64,
+    get_time_us: fn() -> u64,
+    last_refresh_us: u64,
+}
+
+impl FlantermContext {
+    /// Let [`full_refresh`](Self::full_refresh) run at most `hz` times per second
+    ///
+    /// `get_time_us` is a monotonic clock in microseconds; it may wrap
+    /// around. Refreshes requested less than `1_000_000 / hz` microseconds
+    /// after the last one that ran are dropped silently, so the caller must
+    /// refresh again once it stops drawing if the final state has to be on
+    /// screen. The first refresh after this call always runs. An `hz` of 0
+    /// removes the limit.
+    pub fn set_max_refresh_rate(&mut self, hz: u32, get_time_us: fn() -> u64) {
+        if hz == 0 {
+            self.refresh_limit = None;
+            return;
+        }
+        let interval_us = 1_000_000 / hz as u64;
+        self.refresh_limit = Some(RefreshLimit {
+            interval_us,
+            get_time_us,
+            last_refresh_us: get_time_us().wrapping_sub(interval_us),
+        });
+    }
+
+    /// Check whether a full refresh may run now, recording it if so
+    pub(crate) fn take_refresh_slot(&mut self) -> bool {
+        let Some(limit) = &mut self.refresh_limit else {
+            return true;
+        };
+        let now = (limit.get_time_us)();
+        if now.wrapping_sub(limit.last_refresh_us) < limit.interval_us {
+            return false;
+        }
+        limit.last_refresh_us = now;
+        true
+    }
+}
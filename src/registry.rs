@@ -0,0 +1,79 @@
+use crate::{FlantermContext, RegistryError};
+use spin::mutex::Mutex;
+
+/// Identifies one of the globally reachable contexts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContextId {
+    /// The instance set with [`init_global_flanterm`](crate::init_global_flanterm)
+    Global,
+    /// A context added with [`register_global_context`]
+    Named(&'static str),
+    /// A context added with [`register_cpu_context`](crate::register_cpu_context)
+    #[cfg(feature = "per-cpu")]
+    Cpu(usize),
+}
+
+impl ContextId {
+    /// Run `f` on the context, or return `None` if there is none
+    pub fn with<F, R>(self, f: F) -> Option<R>
+    where
+        F: FnOnce(&mut FlantermContext) -> R,
+    {
+        match self {
+            Self::Global => crate::with_global_flanterm(f),
+            Self::Named(name) => with_named_context(name, f),
+            #[cfg(feature = "per-cpu")]
+            Self::Cpu(cpu_id) => crate::with_cpu_context(cpu_id, f),
+        }
+    }
+}
+
+/// Number of contexts [`register_global_context`] can hold at once
+pub const MAX_NAMED_CONTEXTS: usize = 8;
+
+/// Named global contexts protected by a spin mutex
+static REGISTRY: Mutex<[Option<(&'static str, FlantermContext)>; MAX_NAMED_CONTEXTS]> =
+    Mutex::new([const { None }; MAX_NAMED_CONTEXTS]);
+
+/// Make `ctx` globally reachable as `name`, e.g. one context per display output
+///
+/// On error `ctx` is handed back along with the reason. The registry is
+/// separate from the instance set with
+/// [`init_global_flanterm`](crate::init_global_flanterm).
+// Without an allocator there is nothing to box the context into
+#[allow(clippy::result_large_err)]
+pub fn register_global_context(
+    name: &'static str,
+    ctx: FlantermContext,
+) -> Result<(), (RegistryError, FlantermContext)> {
+    let mut registry = REGISTRY.lock();
+    if registry.iter().flatten().any(|(n, _)| *n == name) {
+        return Err((RegistryError::AlreadyRegistered, ctx));
+    }
+    let Some(slot) = registry.iter_mut().find(|slot| slot.is_none()) else {
+        return Err((RegistryError::RegistryFull, ctx));
+    };
+    *slot = Some((name, ctx));
+    Ok(())
+}
+
+/// Run `f` on the context registered as `name`, or return `None` if there is none
+///
+/// The registry stays locked while `f` runs, so `f` must not use it itself.
+pub fn with_named_context<F, R>(name: &'static str, f: F) -> Option<R>
+where
+    F: FnOnce(&mut FlantermContext) -> R,
+{
+    let mut registry = REGISTRY.lock();
+    let (_, ctx) = registry.iter_mut().flatten().find(|(n, _)| *n == name)?;
+    Some(f(ctx))
+}
+
+/// Remove the context registered as `name` and hand it back
+pub fn unregister_global_context(name: &'static str) -> Option<FlantermContext> {
+    let mut registry = REGISTRY.lock();
+    let slot = registry
+        .iter_mut()
+        .find(|slot| slot.as_ref().is_some_and(|(n, _)| *n == name))?;
+    slot.take().map(|(_, ctx)| ctx)
+}
@@ -0,0 +1,158 @@
+use crate::Color;
+
+/// A complete terminal palette: the 16 ANSI colors plus the four defaults
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ColorScheme {
+    /// Normal ANSI colors (SGR 30-37/40-47)
+    pub ansi: [Color; 8],
+    /// Bright ANSI colors (SGR 90-97/100-107)
+    pub ansi_bright: [Color; 8],
+    pub fg: Color,
+    pub bg: Color,
+    /// Default foreground while bold is active
+    pub fg_bright: Color,
+    /// Default background while blink (bright background) is active
+    pub bg_bright: Color,
+}
+
+impl ColorScheme {
+    /// Gruvbox dark
+    pub const GRUVBOX_DARK: Self = Self {
+        ansi: [
+            Color(0x282828),
+            Color(0xcc241d),
+            Color(0x98971a),
+            Color(0xd79921),
+            Color(0x458588),
+            Color(0xb16286),
+            Color(0x689d6a),
+            Color(0xa89984),
+        ],
+        ansi_bright: [
+            Color(0x928374),
+            Color(0xfb4934),
+            Color(0xb8bb26),
+            Color(0xfabd2f),
+            Color(0x83a598),
+            Color(0xd3869b),
+            Color(0x8ec07c),
+            Color(0xebdbb2),
+        ],
+        fg: Color(0xebdbb2),
+        bg: Color(0x282828),
+        fg_bright: Color(0xfbf1c7),
+        bg_bright: Color(0x3c3836),
+    };
+
+    /// Gruvbox light
+    pub const GRUVBOX_LIGHT: Self = Self {
+        ansi: [
+            Color(0xfbf1c7),
+            Color(0xcc241d),
+            Color(0x98971a),
+            Color(0xd79921),
+            Color(0x458588),
+            Color(0xb16286),
+            Color(0x689d6a),
+            Color(0x7c6f64),
+        ],
+        ansi_bright: [
+            Color(0x928374),
+            Color(0x9d0006),
+            Color(0x79740e),
+            Color(0xb57614),
+            Color(0x076678),
+            Color(0x8f3f71),
+            Color(0x427b58),
+            Color(0x3c3836),
+        ],
+        fg: Color(0x3c3836),
+        bg: Color(0xfbf1c7),
+        fg_bright: Color(0x282828),
+        bg_bright: Color(0xebdbb2),
+    };
+
+    /// Solarized dark
+    pub const SOLARIZED_DARK: Self = Self {
+        ansi: [
+            Color(0x073642),
+            Color(0xdc322f),
+            Color(0x859900),
+            Color(0xb58900),
+            Color(0x268bd2),
+            Color(0xd33682),
+            Color(0x2aa198),
+            Color(0xeee8d5),
+        ],
+        ansi_bright: [
+            Color(0x002b36),
+            Color(0xcb4b16),
+            Color(0x586e75),
+            Color(0x657b83),
+            Color(0x839496),
+            Color(0x6c71c4),
+            Color(0x93a1a1),
+            Color(0xfdf6e3),
+        ],
+        fg: Color(0x839496),
+        bg: Color(0x002b36),
+        fg_bright: Color(0x93a1a1),
+        bg_bright: Color(0x073642),
+    };
+
+    /// Nord
+    pub const NORD: Self = Self {
+        ansi: [
+            Color(0x3b4252),
+            Color(0xbf616a),
+            Color(0xa3be8c),
+            Color(0xebcb8b),
+            Color(0x81a1c1),
+            Color(0xb48ead),
+            Color(0x88c0d0),
+            Color(0xe5e9f0),
+        ],
+        ansi_bright: [
+            Color(0x4c566a),
+            Color(0xbf616a),
+            Color(0xa3be8c),
+            Color(0xebcb8b),
+            Color(0x81a1c1),
+            Color(0xb48ead),
+            Color(0x8fbcbb),
+            Color(0xeceff4),
+        ],
+        fg: Color(0xd8dee9),
+        bg: Color(0x2e3440),
+        fg_bright: Color(0xeceff4),
+        bg_bright: Color(0x3b4252),
+    };
+
+    /// Atom One Dark
+    pub const ONE_DARK: Self = Self {
+        ansi: [
+            Color(0x282c34),
+            Color(0xe06c75),
+            Color(0x98c379),
+            Color(0xe5c07b),
+            Color(0x61afef),
+            Color(0xc678dd),
+            Color(0x56b6c2),
+            Color(0xabb2bf),
+        ],
+        ansi_bright: [
+            Color(0x5c6370),
+            Color(0xe06c75),
+            Color(0x98c379),
+            Color(0xd19a66),
+            Color(0x61afef),
+            Color(0xc678dd),
+            Color(0x56b6c2),
+            Color(0xffffff),
+        ],
+        fg: Color(0xabb2bf),
+        bg: Color(0x282c34),
+        fg_bright: Color(0xffffff),
+        bg_bright: Color(0x3e4451),
+    };
+}
@@ -0,0 +1,270 @@
+use crate::bindings::*;
+use crate::{FlantermContext, TextAttributes};
+use core::fmt::Write;
+
+impl FlantermContext {
+    /// Erase from the cursor to the end of the screen (ED 0)
+    pub fn erase_below(&mut self) {
+        self.write_bytes(b"\x1b[0J");
+    }
+
+    /// Erase from the start of the screen up to and including the cursor (ED 1)
+    pub fn erase_above(&mut self) {
+        self.write_bytes(b"\x1b[1J");
+    }
+
+    /// Erase the whole screen without moving the cursor (ED 2)
+    ///
+    /// When the output would be flushed straight away anyway, there is no
+    /// [interceptor](Self::set_interceptor) to see `\x1b[2J`, and the screen
+    /// has the default background and no canvas, this blanks flanterm's grid
+    /// and paints the framebuffer with [`fast_fill`](Self::fast_fill)
+    /// instead of having flanterm redraw every cell. Otherwise `\x1b[2J` is
+    /// sent as usual.
+    pub fn erase_screen(&mut self) {
+        if self.ctx.is_null() || !self.enabled {
+            return;
+        }
+        if self.interceptor.is_some() || !self.flushes_on_write() {
+            self.write_bytes(b"\x1b[2J");
+            return;
+        }
+        let mut color = 0;
+        if unsafe { flanterm_rs_fb_blank_grid(self.ctx, &mut color) } {
+            self.fast_fill(color);
+            // Puts the cursor back
+            self.flush();
+        } else {
+            self.write_bytes(b"\x1b[2J");
+        }
+    }
+
+    /// Erase the scrollback (ED 3)
+    ///
+    /// flanterm keeps no scrollback and treats this like
+    /// [`erase_screen`](Self::erase_screen).
+    pub fn erase_saved_lines(&mut self) {
+        self.write_bytes(b"\x1b[3J");
+    }
+
+    /// Erase from the cursor to the end of the line (EL 0)
+    pub fn erase_to_eol(&mut self) {
+        self.write_bytes(b"\x1b[0K");
+    }
+
+    /// Erase from the start of the line up to the cursor (EL 1)
+    ///
+    /// flanterm leaves the cell under the cursor itself intact, where most
+    /// terminals erase it too.
+    pub fn erase_to_bol(&mut self) {
+        self.write_bytes(b"\x1b[1K");
+    }
+
+    /// Erase the whole line without moving the cursor (EL 2)
+    pub fn erase_line(&mut self) {
+        self.write_bytes(b"\x1b[2K");
+    }
+
+    /// Insert `n` blank lines at the cursor row, pushing the rest of the scroll region down (IL)
+    ///
+    /// Lines pushed past the bottom of the scroll region are lost. Does
+    /// nothing if `n` is 0.
+    pub fn insert_lines(&mut self, n: usize) {
+        let rows = self.get_dimensions().1;
+        if n > 0 {
+            let _ = write!(self, "\x1b[{}L", n.min(rows));
+        }
+    }
+
+    /// Delete `n` lines at the cursor row, pulling the rest of the scroll region up (DL)
+    ///
+    /// Blank lines fill the bottom of the scroll region. flanterm's own DL
+    /// scrolls the whole region regardless of the cursor row, so this is
+    /// done through the framebuffer backend directly instead of `\x1b[nM`.
+    /// Does nothing if `n` is 0 or the cursor is outside the scroll region.
+    pub fn delete_lines(&mut self, n: usize) {
+        if self.ctx.is_null() || !self.enabled || n == 0 {
+            return;
+        }
+        self.drain_write_buffer();
+        unsafe {
+            flanterm_rs_delete_lines(self.ctx, n);
+        }
+    }
+
+    /// Insert `n` blank cells at the cursor, shifting the rest of the line right (ICH)
+    ///
+    /// Cells shifted past the right edge are lost. Does nothing if `n` is 0.
+    pub fn insert_chars(&mut self, n: usize) {
+        let n = n.min(self.cells_right_of_cursor());
+        if n > 0 {
+            let _ = write!(self, "\x1b[{}@", n);
+        }
+    }
+
+    /// Delete `n` cells at the cursor, shifting the rest of the line left (DCH)
+    ///
+    /// Blank cells fill the end of the line. Does nothing if `n` is 0.
+    pub fn delete_chars(&mut self, n: usize) {
+        // flanterm underflows when asked to delete past the end of the line
+        let n = n.min(self.cells_right_of_cursor());
+        if n > 0 {
+            let _ = write!(self, "\x1b[{}P", n);
+        }
+    }
+
+    /// Scroll the scroll region up `n` lines without moving the cursor (SU)
+    ///
+    /// Blank lines fill the bottom of the region. flanterm ignores
+    /// `\x1b[nS`, so this scrolls through the framebuffer backend directly.
+    /// Does nothing if `n` is 0.
+    pub fn scroll_up(&mut self, n: usize) {
+        self.scroll_region(n, false);
+    }
+
+    /// Scroll the scroll region down `n` lines without moving the cursor (SD)
+    ///
+    /// Blank lines fill the top of the region. Like
+    /// [`scroll_up`](Self::scroll_up) this bypasses flanterm's parser, which
+    /// ignores `\x1b[nT`. Does nothing if `n` is 0.
+    pub fn scroll_down(&mut self, n: usize) {
+        self.scroll_region(n, true);
+    }
+
+    fn scroll_region(&mut self, n: usize, down: bool) {
+        if self.ctx.is_null() || !self.enabled || n == 0 {
+            return;
+        }
+        self.drain_write_buffer();
+        unsafe {
+            flanterm_rs_scroll(self.ctx, n, down);
+        }
+    }
+
+    /// Confine scrolling to rows `top..=bottom` (DECSTBM)
+    ///
+    /// Line feeds at `bottom`, [`insert_lines`](Self::insert_lines),
+    /// [`delete_lines`](Self::delete_lines) and the scroll helpers only
+    /// affect the region, leaving the rows around it untouched. The cursor
+    /// moves to the top left corner. Regions that do not satisfy
+    /// `top < bottom < rows` are ignored.
+    pub fn set_scroll_region(&mut self, top: usize, bottom: usize) {
+        let rows = self.get_dimensions().1;
+        if top < bottom && bottom < rows {
+            let _ = write!(self, "\x1b[{};{}r", top + 1, bottom + 1);
+            self.scroll_region = Some((top, bottom));
+        }
+    }
+
+    /// Make the whole screen the scroll region again; the cursor moves to the top left corner
+    pub fn reset_scroll_region(&mut self) {
+        self.write_bytes(b"\x1b[r");
+        self.scroll_region = None;
+    }
+
+    /// Fill every cell with `ch` in the given palette colors
+    ///
+    /// A wide `ch` is repeated half as many times, leaving the last column
+    /// blank on screens an odd number of columns wide.
+    /// The cursor position is restored afterwards and the colors are reset
+    /// to the defaults, as with [`reset_colors`](Self::reset_colors). `None`
+    /// keeps the current color while filling.
+    pub fn fill_screen(&mut self, ch: char, fg: Option<u8>, bg: Option<u8>) {
+        let (cols, rows) = self.get_dimensions();
+        let per_row = cols / Self::char_display_width(ch).max(1);
+        let mut encoded = [0; 4];
+        let ch = ch.encode_utf8(&mut encoded).as_bytes();
+
+        // Batch each row so autoflush does not redraw after every cell
+        let mut row = [0; 256];
+        let per_chunk = row.len() / ch.len();
+        for cell in row.chunks_exact_mut(ch.len()) {
+            cell.copy_from_slice(ch);
+        }
+
+        self.write_bytes(b"\x1b[s");
+        if let Some(fg) = fg {
+            let _ = write!(self, "\x1b[38;5;{}m", fg);
+        }
+        if let Some(bg) = bg {
+            let _ = write!(self, "\x1b[48;5;{}m", bg);
+        }
+        for y in 0..rows {
+            // Address each row absolutely so the last cell of a row never wraps or scrolls
+            let _ = write!(self, "\x1b[{};1H", y + 1);
+            let mut remaining = per_row;
+            while remaining > 0 {
+                let n = remaining.min(per_chunk);
+                self.write_bytes(&row[..n * ch.len()]);
+                remaining -= n;
+            }
+        }
+        self.write_bytes(b"\x1b[u");
+        self.reset_colors();
+    }
+
+    /// Reset modes and attributes to their defaults without touching the screen (DECSTR)
+    ///
+    /// flanterm does not parse `\x1b[!p`: it stops at the `!` and prints the
+    /// `p`. This therefore sends the individual resets flanterm does
+    /// understand:
+    ///
+    /// - SGR attributes and colors (`\x1b[0m`)
+    /// - the scroll region (`\x1b[r`, with the cursor saved around it since
+    ///   flanterm homes the cursor there), which overwrites the
+    ///   [`save_cursor`](Self::save_cursor) slot
+    /// - cursor visibility (`\x1b[?25h`)
+    /// - insert mode (`\x1b[4l`)
+    ///
+    /// Origin mode and auto-wrap, which flanterm ignores, are reset in the
+    /// wrapper's own state. Character sets and tab stops are left alone.
+    pub fn soft_reset(&mut self) {
+        self.write_bytes(b"\x1b[s\x1b[r\x1b[u\x1b[?25h\x1b[4l\x1b[0m");
+        self.attrs = Some(TextAttributes::default());
+        self.scroll_region = None;
+        self.origin_mode = false;
+        self.auto_wrap = true;
+    }
+
+    /// Reset the terminal to its initial state (RIS)
+    ///
+    /// On flanterm `\x1bc` resets the parser state, bold/blink/reverse,
+    /// character sets, the scroll region, insert mode, the tab width (back
+    /// to 8) and cursor visibility, turns autoflush back on, then clears the
+    /// screen and homes the cursor. It keeps the current text colors and
+    /// leaves the palette and default colors as they are now, not as they
+    /// were at init. An SGR reset is sent first so the screen is cleared to
+    /// the default background rather than whatever color was active.
+    ///
+    /// While the wrapper does the flushing itself, e.g. for
+    /// [double buffering](Self::enable_double_buffer), it switches flanterm's
+    /// autoflush back off afterwards and redraws the whole screen. The reset
+    /// is passed to flanterm straight away even with a
+    /// [write buffer](Self::enable_write_buffer).
+    pub fn hard_reset(&mut self) {
+        self.write_bytes(b"\x1b[0m\x1bc");
+        self.attrs = Some(TextAttributes::default());
+        self.scroll_region = None;
+        self.origin_mode = false;
+        self.auto_wrap = true;
+
+        if self.managed_autoflush.is_none() || self.ctx.is_null() || !self.enabled {
+            return;
+        }
+        // flanterm flushed the reset itself, past the wrapper's tracking
+        self.drain_write_buffer();
+        unsafe {
+            flanterm_set_autoflush(self.ctx, false);
+        }
+        self.mark_all_dirty();
+        if self.flushes_on_write() {
+            self.tracked_flush();
+        }
+    }
+
+    /// Number of cells from the cursor to the end of the line, inclusive
+    fn cells_right_of_cursor(&self) -> usize {
+        let cols = self.get_dimensions().0;
+        cols.saturating_sub(self.get_cursor_pos().0)
+    }
+}
@@ -0,0 +1,123 @@
+use crate::FlantermContext;
+use crate::bindings::*;
+
+/// Cells a bitmap of `SELECTIVE_REFRESH_MAX_CELLS / 8` bytes can track
+///
+/// Enough for 480x135 cells, a 4K screen with an 8x16 font, and a good size
+/// for the bitmap given to
+/// [`enable_selective_refresh`](FlantermContext::enable_selective_refresh).
+pub const SELECTIVE_REFRESH_MAX_CELLS: usize = 65536;
+
+/// One bit per cell, set when its contents change
+pub(crate) struct CellBitmap {
+    pub(crate) bits: &'static mut [u8],
+}
+
+impl CellBitmap {
+    /// Number of cells the bitmap covers
+    pub(crate) fn cells(&self) -> usize {
+        self.bits.len() * 8
+    }
+
+    /// Check whether any cell in `start..end` is marked
+    fn any(&self, start: usize, end: usize) -> bool {
+        if end > self.cells() {
+            return true;
+        }
+        (start..end).any(|cell| self.bits[cell / 8] & (1 << (cell % 8)) != 0)
+    }
+}
+
+impl FlantermContext {
+    /// Start recording which character cells change, for [`selective_refresh`](Self::selective_refresh)
+    ///
+    /// Cells are marked as flanterm draws new contents into them, so like
+    /// [dirty tracking](Self::enable_dirty_tracking) this has the wrapper
+    /// take over [autoflush](Self::set_autoflush).
+    ///
+    /// `bitmap` holds one bit per cell, row by row; rows past its end are
+    /// always redrawn. [`SELECTIVE_REFRESH_MAX_CELLS`]` / 8` bytes cover a
+    /// 4K screen. A bitmap set earlier is replaced, and the next refresh
+    /// then redraws every row.
+    pub fn enable_selective_refresh(&mut self, bitmap: &'static mut [u8]) {
+        if self.ctx.is_null() {
+            return;
+        }
+        bitmap.fill(if self.selective_refresh.is_some() {
+            0xff
+        } else {
+            0
+        });
+        self.take_over_flushing();
+        self.selective_refresh = Some(CellBitmap { bits: bitmap });
+    }
+
+    /// Stop recording changed cells, handing back the bitmap set with [`enable_selective_refresh`](Self::enable_selective_refresh)
+    pub fn disable_selective_refresh(&mut self) -> Option<&'static mut [u8]> {
+        let map = self.selective_refresh.take()?;
+        self.release_flushing();
+        Some(map.bits)
+    }
+
+    /// Redraw only the rows with cells that changed since the last refresh
+    ///
+    /// Repaints those rows from flanterm's character grid, e.g. after
+    /// something else drew over them, and is much cheaper than
+    /// [`full_refresh`](Self::full_refresh) when most of the screen is
+    /// static. Pending output is flushed first. Without
+    /// [selective refresh](Self::enable_selective_refresh) enabled this is
+    /// a full refresh.
+    pub fn selective_refresh(&mut self) {
+        if self.selective_refresh.is_none() {
+            self.full_refresh();
+            return;
+        }
+        if self.ctx.is_null() || !self.enabled {
+            return;
+        }
+        self.flush();
+
+        let (cols, rows) = self.get_dimensions();
+        let (mut offset_x, mut offset_y, mut glyph_w, mut glyph_h) = (0, 0, 0, 0);
+        unsafe {
+            flanterm_rs_fb_get_cell_geometry(
+                self.ctx,
+                &mut offset_x,
+                &mut offset_y,
+                &mut glyph_w,
+                &mut glyph_h,
+            );
+        }
+        let Some(map) = &mut self.selective_refresh else {
+            return;
+        };
+        // First and last row redrawn
+        let mut redrawn: Option<(usize, usize)> = None;
+        let mut row = 0;
+        while row < rows {
+            if !map.any(row * cols, (row + 1) * cols) {
+                row += 1;
+                continue;
+            }
+            let start = row;
+            while row < rows && map.any(row * cols, (row + 1) * cols) {
+                row += 1;
+            }
+            unsafe {
+                flanterm_rs_fb_refresh_rows(self.ctx, start, row - start);
+            }
+            redrawn = Some((redrawn.map_or(start, |(first, _)| first), row - 1));
+        }
+        map.bits.fill(0);
+
+        if let Some((first, last)) = redrawn {
+            self.mark_dirty((
+                offset_x,
+                offset_y + first * glyph_h,
+                cols * glyph_w,
+                (last + 1 - first) * glyph_h,
+            ));
+        }
+        self.present();
+    }
+}
@@ -0,0 +1,53 @@
+use crate::{FlantermContext, TextAttributes};
+use core::fmt::Write;
+
+/// Terminal state as tracked by the wrapper, for [`FlantermContext::restore_snapshot`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TerminalSnapshot {
+    /// Absolute cursor position as (column, row)
+    pub cursor: (usize, usize),
+    /// First and last row of the scroll region, inclusive
+    pub scroll_region: (usize, usize),
+    pub auto_wrap: bool,
+    pub origin_mode: bool,
+    /// Attributes set through the wrapper; defaults if none were known
+    pub attrs: TextAttributes,
+}
+
+impl FlantermContext {
+    /// Capture the cursor position and the modes and attributes set through the wrapper
+    ///
+    /// Only the cursor is read back from flanterm; everything else is what
+    /// this crate's helpers last set, so state changed by raw escape
+    /// sequences is not captured.
+    pub fn snapshot(&self) -> TerminalSnapshot {
+        let rows = self.get_dimensions().1;
+        TerminalSnapshot {
+            cursor: self.get_cursor_pos(),
+            scroll_region: self.scroll_region.unwrap_or((0, rows.saturating_sub(1))),
+            auto_wrap: self.auto_wrap,
+            origin_mode: self.origin_mode,
+            attrs: self.attrs.unwrap_or_default(),
+        }
+    }
+
+    /// Write the escape sequences that bring the terminal back to `snap`
+    ///
+    /// Screen contents are not part of the snapshot and stay as they are.
+    pub fn restore_snapshot(&mut self, snap: &TerminalSnapshot) {
+        let rows = self.get_dimensions().1;
+        let (top, bottom) = snap.scroll_region;
+        if top == 0 && bottom + 1 >= rows {
+            self.reset_scroll_region();
+        } else {
+            self.set_scroll_region(top, bottom);
+        }
+        self.set_origin_mode(snap.origin_mode);
+        self.set_auto_wrap(snap.auto_wrap);
+        self.reset_format();
+        self.apply_attrs(&snap.attrs);
+        // Absolute, so origin mode does not offset it
+        let (x, y) = snap.cursor;
+        let _ = write!(self, "\x1b[{};{}H", y + 1, x + 1);
+    }
+}
@@ -0,0 +1,44 @@
+use crate::unicode::{str_width, truncate_start_to_width};
+use crate::{Align, FlantermContext, TextAttributes};
+
+impl FlantermContext {
+    /// Show `text` on the bottom row, filling the rest of the row in `attrs`
+    ///
+    /// The cursor and the current attributes are restored afterwards. The
+    /// status line scrolls away with the rest of the screen; a
+    /// [scroll region](Self::set_scroll_region) ending one row above the
+    /// bottom keeps it in place.
+    pub fn set_status_line(&mut self, text: &str, attrs: &TextAttributes) {
+        self.set_status_line_split(text, "", attrs);
+    }
+
+    /// Like [`set_status_line`](Self::set_status_line) with `right` aligned to the right edge
+    ///
+    /// `right` wins if both do not fit: `left` is cut short first, then the
+    /// start of `right`.
+    pub fn set_status_line_split(&mut self, left: &str, right: &str, attrs: &TextAttributes) {
+        let rows = self.get_dimensions().1;
+        if rows == 0 {
+            return;
+        }
+        self.push_attrs();
+        self.reset_format();
+        attrs.apply(self);
+        self.at_position(0, rows - 1, |ctx, cols| {
+            let right = truncate_start_to_width(right, cols);
+            let right_cols = str_width(right);
+            ctx.write_padded(left, cols - right_cols, Align::Left, ' ');
+            ctx.write_bytes(right.as_bytes());
+        });
+        self.pop_attrs();
+    }
+
+    /// Erase the bottom row in the current background color
+    pub fn clear_status_line(&mut self) {
+        let rows = self.get_dimensions().1;
+        if rows == 0 {
+            return;
+        }
+        self.at_position(0, rows - 1, |ctx, _| ctx.erase_line());
+    }
+}
@@ -0,0 +1,33 @@
+use crate::FlantermContext;
+use core::fmt::{self, Write};
+use core::ops::DerefMut;
+use spin::mutex::Mutex;
+
+/// A context behind a spin mutex, for keeping a terminal of your own in a `static`
+///
+/// Contexts are created at runtime, so the static itself is usually a
+/// `spin::Once<SyncFlantermContext>`. `&SyncFlantermContext` implements
+/// [`Write`], taking the lock once for each `write!`.
+pub struct SyncFlantermContext(Mutex<FlantermContext>);
+
+impl SyncFlantermContext {
+    /// Wrap `ctx` for shared use
+    pub const fn new(ctx: FlantermContext) -> Self {
+        Self(Mutex::new(ctx))
+    }
+
+    /// Lock the context, spinning until it is free
+    pub fn lock(&self) -> impl DerefMut<Target = FlantermContext> {
+        self.0.lock()
+    }
+}
+
+impl Write for &SyncFlantermContext {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.lock().write_str(s)
+    }
+
+    fn write_fmt(&mut self, args: fmt::Arguments<'_>) -> fmt::Result {
+        self.lock().write_fmt(args)
+    }
+}
@@ -0,0 +1,277 @@
+use crate::unicode::{str_width, truncate_start_to_width, truncate_to_width};
+use crate::{FlantermContext, TextAttributes};
+use core::fmt::{self, Write};
+
+/// Where text sits within a wider field
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Align {
+    #[default]
+    Left,
+    Center,
+    Right,
+}
+
+/// Text padded or cut to a fixed number of columns, as a [`Display`](fmt::Display) value
+///
+/// Created with [`padded`]; [`FlantermContext::write_padded`] writes the
+/// same output directly.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Padded<'a> {
+    text: &'a str,
+    width: usize,
+    align: Align,
+    fill: char,
+}
+
+/// Pad `text` with `fill` to `width` columns, or cut it to fit
+///
+/// Widths are measured in columns, so wide characters count twice. If
+/// `fill` is a wide character, a leftover odd column is filled with a space.
+pub fn padded(text: &str, width: usize, align: Align, fill: char) -> Padded<'_> {
+    Padded {
+        text,
+        width,
+        align,
+        fill,
+    }
+}
+
+impl<'a> Padded<'a> {
+    /// The text to write and the number of fill columns on either side of it
+    fn layout(&self) -> (&'a str, usize, usize) {
+        let text = truncate_to_width(self.text, self.width);
+        let pad = self.width - str_width(text);
+        let (left, right) = match self.align {
+            Align::Left => (0, pad),
+            Align::Center => (pad / 2, pad - pad / 2),
+            Align::Right => (pad, 0),
+        };
+        (text, left, right)
+    }
+
+    /// How many fill characters and trailing spaces cover `columns`
+    fn fill_count(&self, columns: usize) -> (usize, usize) {
+        let fill_width = FlantermContext::char_display_width(self.fill).max(1);
+        (columns / fill_width, columns % fill_width)
+    }
+
+    fn write_fill(&self, f: &mut impl Write, columns: usize) -> fmt::Result {
+        let (fills, spaces) = self.fill_count(columns);
+        for _ in 0..fills {
+            f.write_char(self.fill)?;
+        }
+        for _ in 0..spaces {
+            f.write_char(' ')?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Padded<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (text, left, right) = self.layout();
+        self.write_fill(f, left)?;
+        f.write_str(text)?;
+        self.write_fill(f, right)
+    }
+}
+
+impl FlantermContext {
+    /// Write `text` at 0-indexed (`col`, `row`) and put the cursor back where it was
+    ///
+    /// Coordinates are absolute, ignoring [origin mode](Self::set_origin_mode),
+    /// and nothing is written if they are off screen. Text that would run
+    /// past the right edge is cut off instead of wrapping; it should not
+    /// contain control characters. Neither cursor save slot is touched.
+    pub fn write_at(&mut self, col: usize, row: usize, text: &str) {
+        self.at_position(col, row, |ctx, room| {
+            ctx.write_bytes(truncate_to_width(text, room).as_bytes());
+        });
+    }
+
+    /// Like [`write_at`](Self::write_at), drawing the text in `attrs`
+    ///
+    /// `attrs` apply on top of reset formatting, and the attributes in
+    /// effect before are restored as with [`pop_attrs`](Self::pop_attrs).
+    pub fn write_at_colored(&mut self, col: usize, row: usize, text: &str, attrs: &TextAttributes) {
+        self.push_attrs();
+        self.reset_format();
+        attrs.apply(self);
+        self.write_at(col, row, text);
+        self.pop_attrs();
+    }
+
+    /// Write `text` centered on `row`, putting the cursor back afterwards
+    ///
+    /// Text wider than the screen is cut off with `...`: flanterm's fonts
+    /// only cover code page 437, which has no `…` glyph.
+    pub fn write_centered(&mut self, row: usize, text: &str) {
+        let cols = self.get_dimensions().0;
+        let (text, dots) = ellipsize(text, cols);
+        let col = (cols - str_width(text) - dots) / 2;
+        self.at_position(col, row, |ctx, _| {
+            ctx.write_bytes(text.as_bytes());
+            ctx.write_bytes(&b"..."[..dots]);
+        });
+    }
+
+    /// Like [`write_centered`](Self::write_centered) in bold, restoring the attributes afterwards
+    pub fn write_centered_bold(&mut self, row: usize, text: &str) {
+        self.push_attrs();
+        self.set_bold();
+        self.write_centered(row, text);
+        self.pop_attrs();
+    }
+
+    /// Write `text` on `row` so that it ends in the last column, putting the cursor back afterwards
+    ///
+    /// Text wider than the screen loses characters from the start.
+    pub fn write_right_aligned(&mut self, row: usize, text: &str) {
+        let cols = self.get_dimensions().0;
+        let text = truncate_start_to_width(text, cols);
+        self.at_position(cols - str_width(text), row, |ctx, _| {
+            ctx.write_bytes(text.as_bytes());
+        });
+    }
+
+    /// Write `text` padded with `fill` or cut to exactly `width` columns
+    ///
+    /// See [`padded`] for the `Display` equivalent.
+    pub fn write_padded(&mut self, text: &str, width: usize, align: Align, fill: char) {
+        let padded = padded(text, width, align, fill);
+        let (text, left, right) = padded.layout();
+        self.write_fill(&padded, left);
+        self.write_bytes(text.as_bytes());
+        self.write_fill(&padded, right);
+    }
+
+    fn write_fill(&mut self, padded: &Padded<'_>, columns: usize) {
+        let (fills, spaces) = padded.fill_count(columns);
+        let _ = self.write_char_n(padded.fill, fills);
+        let _ = self.write_char_n(' ', spaces);
+    }
+
+    /// Write at most `max_cols` columns of `text`, ending in `...` if it had to be cut
+    ///
+    /// Widths are measured per character, so multi-byte and wide characters
+    /// are never split; a wide character that would straddle the dots is
+    /// dropped, leaving the text one column short.
+    pub fn write_truncated(&mut self, text: &str, max_cols: usize) {
+        let (text, dots) = ellipsize(text, max_cols);
+        self.write_bytes(text.as_bytes());
+        self.write_bytes(&b"..."[..dots]);
+    }
+
+    /// Run `f` with the cursor at absolute (`col`, `row`), then move the cursor back
+    ///
+    /// `f` is passed the number of columns left on the row and is not run
+    /// if the position is off screen.
+    pub(crate) fn at_position(&mut self, col: usize, row: usize, f: impl FnOnce(&mut Self, usize)) {
+        let (cols, rows) = self.get_dimensions();
+        if col >= cols || row >= rows {
+            return;
+        }
+        let (x, y) = self.get_cursor_pos();
+        let _ = write!(self, "\x1b[{};{}H", row + 1, col + 1);
+        f(self, cols - col);
+        let _ = write!(self, "\x1b[{};{}H", y + 1, x + 1);
+    }
+
+    /// Write formatted output and return the number of columns it takes up
+    ///
+    /// Wide characters count as two columns and combining characters and
+    /// control characters as none, so the result is only the cursor movement
+    /// for text without line breaks or escape sequences.
+    pub fn write_fmt_count(&mut self, args: fmt::Arguments<'_>) -> usize {
+        let mut counter = ColumnCounter {
+            ctx: self,
+            columns: 0,
+        };
+        let _ = counter.write_fmt(args);
+        counter.columns
+    }
+}
+
+/// Writes through to a context while adding up the width of what passes
+struct ColumnCounter<'a> {
+    ctx: &'a mut FlantermContext,
+    columns: usize,
+}
+
+impl Write for ColumnCounter<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.columns += str_width(s);
+        self.ctx.write_bytes(s.as_bytes());
+        Ok(())
+    }
+}
+
+/// Writes through to a context, dropping whatever does not fit in the remaining columns
+pub(crate) struct ClippedWriter<'a> {
+    ctx: &'a mut FlantermContext,
+    room: usize,
+}
+
+impl<'a> ClippedWriter<'a> {
+    pub(crate) fn new(ctx: &'a mut FlantermContext, room: usize) -> Self {
+        Self { ctx, room }
+    }
+}
+
+impl Write for ClippedWriter<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let s = truncate_to_width(s, self.room);
+        self.room -= str_width(s);
+        self.ctx.write_bytes(s.as_bytes());
+        Ok(())
+    }
+}
+
+/// Cut `text` down to `max_cols` columns, leaving room for trailing dots if it does not fit
+///
+/// Returns the part of `text` to write and how many `.` should follow it.
+pub(crate) fn ellipsize(text: &str, max_cols: usize) -> (&str, usize) {
+    if str_width(text) <= max_cols {
+        (text, 0)
+    } else {
+        (
+            truncate_to_width(text, max_cols.saturating_sub(3)),
+            max_cols.min(3),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use std::string::ToString;
+
+    #[test]
+    fn ellipsize_short_text_unchanged() {
+        assert_eq!(ellipsize("hello", 5), ("hello", 0));
+        assert_eq!(ellipsize("", 0), ("", 0));
+    }
+
+    #[test]
+    fn ellipsize_long_text() {
+        assert_eq!(ellipsize("hello world", 8), ("hello", 3));
+        // Too narrow for any text, only dots fit
+        assert_eq!(ellipsize("hello", 3), ("", 3));
+        assert_eq!(ellipsize("hello", 2), ("", 2));
+        assert_eq!(ellipsize("hello", 0), ("", 0));
+        // A wide character that does not fit is left out entirely
+        assert_eq!(ellipsize("a漢字b", 5), ("a", 3));
+    }
+
+    #[test]
+    fn padding() {
+        assert_eq!(padded("ab", 5, Align::Left, '.').to_string(), "ab...");
+        assert_eq!(padded("ab", 5, Align::Center, '.').to_string(), ".ab..");
+        assert_eq!(padded("ab", 5, Align::Right, '.').to_string(), "...ab");
+        assert_eq!(padded("abcdef", 4, Align::Right, '.').to_string(), "abcd");
+        // An odd column left by a wide fill character becomes a space
+        assert_eq!(padded("漢", 5, Align::Left, '字').to_string(), "漢字 ");
+    }
+}
@@ -0,0 +1,338 @@
+//! Character width lookup matching flanterm's own
+//!
+//! flanterm sizes characters with Markus Kuhn's `wcwidth`, which derives
+//! from the Unicode East Asian Width property. The tables here are the same
+//! ones, so the widths agree with what flanterm actually draws, even where
+//! newer Unicode versions disagree (e.g. most emoji are one column here).
+
+use crate::FlantermContext;
+
+/// Non-spacing characters, drawn in zero columns
+static COMBINING: &[(u32, u32)] = &[
+    (0x0300, 0x036f),
+    (0x0483, 0x0486),
+    (0x0488, 0x0489),
+    (0x0591, 0x05bd),
+    (0x05bf, 0x05bf),
+    (0x05c1, 0x05c2),
+    (0x05c4, 0x05c5),
+    (0x05c7, 0x05c7),
+    (0x0600, 0x0603),
+    (0x0610, 0x0615),
+    (0x064b, 0x065e),
+    (0x0670, 0x0670),
+    (0x06d6, 0x06e4),
+    (0x06e7, 0x06e8),
+    (0x06ea, 0x06ed),
+    (0x070f, 0x070f),
+    (0x0711, 0x0711),
+    (0x0730, 0x074a),
+    (0x07a6, 0x07b0),
+    (0x07eb, 0x07f3),
+    (0x0901, 0x0902),
+    (0x093c, 0x093c),
+    (0x0941, 0x0948),
+    (0x094d, 0x094d),
+    (0x0951, 0x0954),
+    (0x0962, 0x0963),
+    (0x0981, 0x0981),
+    (0x09bc, 0x09bc),
+    (0x09c1, 0x09c4),
+    (0x09cd, 0x09cd),
+    (0x09e2, 0x09e3),
+    (0x0a01, 0x0a02),
+    (0x0a3c, 0x0a3c),
+    (0x0a41, 0x0a42),
+    (0x0a47, 0x0a48),
+    (0x0a4b, 0x0a4d),
+    (0x0a70, 0x0a71),
+    (0x0a81, 0x0a82),
+    (0x0abc, 0x0abc),
+    (0x0ac1, 0x0ac5),
+    (0x0ac7, 0x0ac8),
+    (0x0acd, 0x0acd),
+    (0x0ae2, 0x0ae3),
+    (0x0b01, 0x0b01),
+    (0x0b3c, 0x0b3c),
+    (0x0b3f, 0x0b3f),
+    (0x0b41, 0x0b43),
+    (0x0b4d, 0x0b4d),
+    (0x0b56, 0x0b56),
+    (0x0b82, 0x0b82),
+    (0x0bc0, 0x0bc0),
+    (0x0bcd, 0x0bcd),
+    (0x0c3e, 0x0c40),
+    (0x0c46, 0x0c48),
+    (0x0c4a, 0x0c4d),
+    (0x0c55, 0x0c56),
+    (0x0cbc, 0x0cbc),
+    (0x0cbf, 0x0cbf),
+    (0x0cc6, 0x0cc6),
+    (0x0ccc, 0x0ccd),
+    (0x0ce2, 0x0ce3),
+    (0x0d41, 0x0d43),
+    (0x0d4d, 0x0d4d),
+    (0x0dca, 0x0dca),
+    (0x0dd2, 0x0dd4),
+    (0x0dd6, 0x0dd6),
+    (0x0e31, 0x0e31),
+    (0x0e34, 0x0e3a),
+    (0x0e47, 0x0e4e),
+    (0x0eb1, 0x0eb1),
+    (0x0eb4, 0x0eb9),
+    (0x0ebb, 0x0ebc),
+    (0x0ec8, 0x0ecd),
+    (0x0f18, 0x0f19),
+    (0x0f35, 0x0f35),
+    (0x0f37, 0x0f37),
+    (0x0f39, 0x0f39),
+    (0x0f71, 0x0f7e),
+    (0x0f80, 0x0f84),
+    (0x0f86, 0x0f87),
+    (0x0f90, 0x0f97),
+    (0x0f99, 0x0fbc),
+    (0x0fc6, 0x0fc6),
+    (0x102d, 0x1030),
+    (0x1032, 0x1032),
+    (0x1036, 0x1037),
+    (0x1039, 0x1039),
+    (0x1058, 0x1059),
+    (0x1160, 0x11ff),
+    (0x135f, 0x135f),
+    (0x1712, 0x1714),
+    (0x1732, 0x1734),
+    (0x1752, 0x1753),
+    (0x1772, 0x1773),
+    (0x17b4, 0x17b5),
+    (0x17b7, 0x17bd),
+    (0x17c6, 0x17c6),
+    (0x17c9, 0x17d3),
+    (0x17dd, 0x17dd),
+    (0x180b, 0x180d),
+    (0x18a9, 0x18a9),
+    (0x1920, 0x1922),
+    (0x1927, 0x1928),
+    (0x1932, 0x1932),
+    (0x1939, 0x193b),
+    (0x1a17, 0x1a18),
+    (0x1b00, 0x1b03),
+    (0x1b34, 0x1b34),
+    (0x1b36, 0x1b3a),
+    (0x1b3c, 0x1b3c),
+    (0x1b42, 0x1b42),
+    (0x1b6b, 0x1b73),
+    (0x1dc0, 0x1dca),
+    (0x1dfe, 0x1dff),
+    (0x200b, 0x200f),
+    (0x202a, 0x202e),
+    (0x2060, 0x2063),
+    (0x206a, 0x206f),
+    (0x20d0, 0x20ef),
+    (0x302a, 0x302f),
+    (0x3099, 0x309a),
+    (0xa806, 0xa806),
+    (0xa80b, 0xa80b),
+    (0xa825, 0xa826),
+    (0xfb1e, 0xfb1e),
+    (0xfe00, 0xfe0f),
+    (0xfe20, 0xfe23),
+    (0xfeff, 0xfeff),
+    (0xfff9, 0xfffb),
+    (0x10a01, 0x10a03),
+    (0x10a05, 0x10a06),
+    (0x10a0c, 0x10a0f),
+    (0x10a38, 0x10a3a),
+    (0x10a3f, 0x10a3f),
+    (0x1d167, 0x1d169),
+    (0x1d173, 0x1d182),
+    (0x1d185, 0x1d18b),
+    (0x1d1aa, 0x1d1ad),
+    (0x1d242, 0x1d244),
+    (0xe0001, 0xe0001),
+    (0xe0020, 0xe007f),
+    (0xe0100, 0xe01ef),
+];
+
+/// Characters drawn across two columns
+static WIDE: &[(u32, u32)] = &[
+    (0x1100, 0x115f),
+    (0x2329, 0x232a),
+    (0x2e80, 0x303e),
+    (0x3040, 0xa4cf),
+    (0xac00, 0xd7a3),
+    (0xf900, 0xfaff),
+    (0xfe10, 0xfe19),
+    (0xfe30, 0xfe6f),
+    (0xff00, 0xff60),
+    (0xffe0, 0xffe6),
+    (0x20000, 0x2fffd),
+    (0x30000, 0x3fffd),
+];
+
+/// Characters outside ASCII that flanterm's `unicode_to_cp437` maps to a glyph
+static CP437: &[u32] = &[
+    0x00a1, 0x00a2, 0x00a3, 0x00a5, 0x00a7, 0x00aa, 0x00ab, 0x00ac, 0x00b0, 0x00b1, 0x00b2, 0x00b5,
+    0x00b6, 0x00b7, 0x00ba, 0x00bb, 0x00bc, 0x00bd, 0x00bf, 0x00c4, 0x00c5, 0x00c6, 0x00c7, 0x00c9,
+    0x00d1, 0x00d6, 0x00dc, 0x00df, 0x00e0, 0x00e1, 0x00e2, 0x00e4, 0x00e5, 0x00e6, 0x00e7, 0x00e8,
+    0x00e9, 0x00ea, 0x00eb, 0x00ec, 0x00ed, 0x00ee, 0x00ef, 0x00f1, 0x00f2, 0x00f3, 0x00f4, 0x00f6,
+    0x00f7, 0x00f9, 0x00fa, 0x00fb, 0x00fc, 0x00ff, 0x0192, 0x0393, 0x0398, 0x03a3, 0x03a6, 0x03a9,
+    0x03b1, 0x03b4, 0x03b5, 0x03c0, 0x03c3, 0x03c4, 0x03c6, 0x2022, 0x203c, 0x207f, 0x20a7, 0x2190,
+    0x2191, 0x2192, 0x2193, 0x2194, 0x2195, 0x21a8, 0x2219, 0x221a, 0x221e, 0x221f, 0x2229, 0x2248,
+    0x2261, 0x2264, 0x2265, 0x2302, 0x2310, 0x2320, 0x2321, 0x2500, 0x2502, 0x250c, 0x2510, 0x2514,
+    0x2518, 0x251c, 0x2524, 0x252c, 0x2534, 0x253c, 0x2550, 0x2551, 0x2552, 0x2553, 0x2554, 0x2555,
+    0x2556, 0x2557, 0x2558, 0x2559, 0x255a, 0x255b, 0x255c, 0x255d, 0x255e, 0x255f, 0x2560, 0x2561,
+    0x2562, 0x2563, 0x2564, 0x2565, 0x2566, 0x2567, 0x2568, 0x2569, 0x256a, 0x256b, 0x256c, 0x2580,
+    0x2584, 0x2588, 0x258c, 0x2590, 0x2591, 0x2592, 0x2593, 0x25a0, 0x25ac, 0x25b2, 0x25ba, 0x25bc,
+    0x25c4, 0x25cb, 0x25d8, 0x25d9, 0x263a, 0x263b, 0x263c, 0x2640, 0x2642, 0x2660, 0x2663, 0x2665,
+    0x2666, 0x266a, 0x266b,
+];
+
+fn in_table(ch: char, table: &[(u32, u32)]) -> bool {
+    let ch = ch as u32;
+    table
+        .binary_search_by(|&(start, end)| {
+            if end < ch {
+                core::cmp::Ordering::Less
+            } else if start > ch {
+                core::cmp::Ordering::Greater
+            } else {
+                core::cmp::Ordering::Equal
+            }
+        })
+        .is_ok()
+}
+
+/// Check whether `ch` takes up two columns
+pub fn is_wide(ch: char) -> bool {
+    in_table(ch, WIDE)
+}
+
+/// Check whether `ch` is a combining or other zero-width character
+pub fn is_combining(ch: char) -> bool {
+    in_table(ch, COMBINING)
+}
+
+/// Check whether flanterm has a glyph of its own for `ch`
+///
+/// Anything else that takes up columns is drawn as a block, or as the
+/// character set with [`set_glyph_fallback`](FlantermContext::set_glyph_fallback).
+pub(crate) fn has_glyph(ch: char) -> bool {
+    matches!(ch, ' '..='~') || CP437.binary_search(&(ch as u32)).is_ok()
+}
+
+/// Number of columns `s` takes up
+pub fn str_width(s: &str) -> usize {
+    s.chars().map(FlantermContext::char_display_width).sum()
+}
+
+/// Longest prefix of `s` that fits in `width` columns
+pub fn truncate_to_width(s: &str, width: usize) -> &str {
+    let mut used = 0;
+    for (i, ch) in s.char_indices() {
+        used += FlantermContext::char_display_width(ch);
+        if used > width {
+            return &s[..i];
+        }
+    }
+    s
+}
+
+/// Longest suffix of `s` that fits in `width` columns
+pub fn truncate_start_to_width(s: &str, width: usize) -> &str {
+    let mut used = 0;
+    for (i, ch) in s.char_indices().rev() {
+        used += FlantermContext::char_display_width(ch);
+        if used > width {
+            return &s[i + ch.len_utf8()..];
+        }
+    }
+    s
+}
+
+impl FlantermContext {
+    /// Number of columns flanterm advances by for `ch`: 0, 1 or 2
+    ///
+    /// Combining characters and C0 controls take no columns of their own.
+    pub fn char_display_width(ch: char) -> usize {
+        if ch < ' ' || ch == '\u{7f}' || is_combining(ch) {
+            0
+        } else if is_wide(ch) {
+            2
+        } else {
+            1
+        }
+    }
+
+    /// Draw characters flanterm has no glyph for as `ch`
+    ///
+    /// flanterm draws them as a block (CP437 0xFE) by default. `ch` has to
+    /// be printable ASCII, which every font has a glyph for; any other
+    /// character restores the block. Characters two columns wide get the
+    /// fallback and a blank cell, combining characters are still dropped.
+    /// Output through a [`FlantermHandle`](crate::FlantermHandle) always
+    /// uses the block.
+    pub fn set_glyph_fallback(&mut self, ch: char) {
+        self.glyph_fallback = matches!(ch, ' '..='~').then_some(ch as u8);
+    }
+
+    /// Write a character that takes up two columns
+    ///
+    /// flanterm draws wide characters it has no glyph for as a block
+    /// followed by a blank cell. Narrower characters are padded with spaces
+    /// so the cursor always moves exactly two columns.
+    pub fn write_wide_char(&mut self, ch: char) {
+        let _ = self.write_char(ch);
+        for _ in Self::char_display_width(ch)..2 {
+            self.write_bytes(b" ");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn char_widths() {
+        assert_eq!(FlantermContext::char_display_width('a'), 1);
+        assert_eq!(FlantermContext::char_display_width('\n'), 0);
+        assert_eq!(FlantermContext::char_display_width('\u{7f}'), 0);
+        assert_eq!(FlantermContext::char_display_width('\u{301}'), 0);
+        assert_eq!(FlantermContext::char_display_width('漢'), 2);
+        assert_eq!(FlantermContext::char_display_width('\u{ff21}'), 2);
+        // Markus Kuhn's tables predate wide emoji
+        assert_eq!(FlantermContext::char_display_width('😀'), 1);
+    }
+
+    #[test]
+    fn str_widths() {
+        assert_eq!(str_width(""), 0);
+        assert_eq!(str_width("abc"), 3);
+        assert_eq!(str_width("e\u{301}"), 1);
+        assert_eq!(str_width("a漢字"), 5);
+    }
+
+    #[test]
+    fn truncation_keeps_whole_characters() {
+        assert_eq!(truncate_to_width("abc", 5), "abc");
+        assert_eq!(truncate_to_width("abc", 2), "ab");
+        assert_eq!(truncate_to_width("a漢字", 2), "a");
+        assert_eq!(truncate_to_width("a漢字", 3), "a漢");
+        // Combining marks stay with their base character
+        assert_eq!(truncate_to_width("ae\u{301}b", 2), "ae\u{301}");
+
+        assert_eq!(truncate_start_to_width("abc", 2), "bc");
+        assert_eq!(truncate_start_to_width("漢字a", 2), "a");
+        assert_eq!(truncate_start_to_width("漢字a", 3), "字a");
+        assert_eq!(truncate_start_to_width("abc", 0), "");
+    }
+
+    #[test]
+    fn glyph_coverage() {
+        assert!(has_glyph('a'));
+        assert!(has_glyph('é'));
+        assert!(has_glyph('█'));
+        assert!(!has_glyph('€'));
+        assert!(!has_glyph('漢'));
+    }
+}
@@ -0,0 +1,201 @@
+use crate::{FlantermContext, InvalidUtf8Error};
+use core::str;
+
+/// U+FFFD REPLACEMENT CHARACTER
+const REPLACEMENT: &[u8] = "\u{fffd}".as_bytes();
+
+/// UTF-8 validation state carried between writes
+#[derive(Clone, Copy)]
+pub(crate) struct Utf8Validator {
+    pub(crate) strict: bool,
+    /// Start of a multi-byte sequence that the last write ended in the middle of
+    pending: [u8; 4],
+    pending_len: usize,
+}
+
+impl Utf8Validator {
+    pub(crate) const fn new() -> Self {
+        Self {
+            strict: false,
+            pending: [0; 4],
+            pending_len: 0,
+        }
+    }
+
+    /// Validate the next piece of output, passing it on to `out` in valid chunks
+    ///
+    /// Invalid sequences become U+FFFD, or end the piece with an error in
+    /// strict mode. A sequence cut off at the end is kept until the next
+    /// piece completes or breaks it.
+    pub(crate) fn feed(
+        &mut self,
+        mut bytes: &[u8],
+        mut out: impl FnMut(&[u8]),
+    ) -> Result<(), InvalidUtf8Error> {
+        let total = bytes.len();
+
+        if self.pending_len > 0 {
+            let mut seq = self.pending;
+            let mut len = self.pending_len;
+            let mut used = 0;
+            let valid = loop {
+                if used == bytes.len() {
+                    self.pending = seq;
+                    self.pending_len = len;
+                    return Ok(());
+                }
+                seq[len] = bytes[used];
+                len += 1;
+                used += 1;
+                match str::from_utf8(&seq[..len]) {
+                    Ok(_) => break true,
+                    // The byte just added does not belong to the sequence
+                    Err(err) if err.error_len().is_some() => {
+                        len -= 1;
+                        used -= 1;
+                        break false;
+                    }
+                    Err(_) => {}
+                }
+            };
+            self.pending_len = 0;
+            if valid {
+                out(&seq[..len]);
+            } else if self.strict {
+                return Err(InvalidUtf8Error { valid_up_to: 0 });
+            } else {
+                out(REPLACEMENT);
+            }
+            bytes = &bytes[used..];
+        }
+
+        loop {
+            let err = match str::from_utf8(bytes) {
+                Ok(_) => {
+                    out(bytes);
+                    return Ok(());
+                }
+                Err(err) => err,
+            };
+            let (valid, rest) = bytes.split_at(err.valid_up_to());
+            out(valid);
+            match err.error_len() {
+                Some(_) if self.strict => {
+                    return Err(InvalidUtf8Error {
+                        valid_up_to: total - rest.len(),
+                    });
+                }
+                Some(len) => {
+                    out(REPLACEMENT);
+                    bytes = &rest[len..];
+                }
+                None => {
+                    self.pending[..rest.len()].copy_from_slice(rest);
+                    self.pending_len = rest.len();
+                    return Ok(());
+                }
+            }
+        }
+    }
+}
+
+impl FlantermContext {
+    /// Reject malformed UTF-8 instead of replacing it
+    ///
+    /// By default [`write_bytes`](Self::write_bytes) replaces every invalid
+    /// sequence with U+FFFD, because flanterm would otherwise draw the stray
+    /// bytes as code page 437 glyphs. In strict mode a write stops at the
+    /// first invalid sequence: [`try_write_bytes`](Self::try_write_bytes)
+    /// reports it, while `write_bytes` drops the rest silently.
+    pub fn set_strict_utf8(&mut self, strict: bool) {
+        self.utf8.strict = strict;
+    }
+
+    /// Check whether malformed UTF-8 is rejected rather than replaced
+    pub fn strict_utf8(&self) -> bool {
+        self.utf8.strict
+    }
+
+    /// Write raw bytes to the terminal, reporting malformed UTF-8 in strict mode
+    ///
+    /// A multi-byte character split across two writes is held back until
+    /// its last byte arrives. Outside strict mode this never fails.
+    pub fn try_write_bytes(&mut self, bytes: &[u8]) -> Result<(), InvalidUtf8Error> {
+        if self.ctx.is_null() || !self.enabled {
+            return Ok(());
+        }
+        // Writing never touches the validator, so run a copy of it to leave
+        // `self` free for the writes
+        let mut utf8 = self.utf8;
+        let result = utf8.feed(bytes, |valid| self.write_validated(valid));
+        self.utf8 = utf8;
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use std::vec::Vec;
+
+    fn feed(
+        validator: &mut Utf8Validator,
+        bytes: &[u8],
+    ) -> (Vec<u8>, Result<(), InvalidUtf8Error>) {
+        let mut out = Vec::new();
+        let result = validator.feed(bytes, |valid| out.extend_from_slice(valid));
+        (out, result)
+    }
+
+    #[test]
+    fn valid_input_passes_through() {
+        let mut validator = Utf8Validator::new();
+        assert_eq!(
+            feed(&mut validator, "a€b".as_bytes()),
+            ("a€b".into(), Ok(()))
+        );
+    }
+
+    #[test]
+    fn split_character_is_held_back() {
+        let mut validator = Utf8Validator::new();
+        assert_eq!(feed(&mut validator, b"a\xe2\x82"), (b"a".into(), Ok(())));
+        assert_eq!(feed(&mut validator, b""), (b"".into(), Ok(())));
+        assert_eq!(feed(&mut validator, b"\xacb"), ("€b".into(), Ok(())));
+    }
+
+    #[test]
+    fn broken_split_character_is_replaced() {
+        let mut validator = Utf8Validator::new();
+        assert_eq!(feed(&mut validator, b"\xe2\x82"), (b"".into(), Ok(())));
+        assert_eq!(feed(&mut validator, b"b"), ("\u{fffd}b".into(), Ok(())));
+    }
+
+    #[test]
+    fn overlong_encodings_are_replaced() {
+        let mut validator = Utf8Validator::new();
+        let (out, result) = feed(&mut validator, b"\xc0\xafx\xe0\x80\xaf");
+        assert_eq!(result, Ok(()));
+        assert!(str::from_utf8(&out).is_ok());
+        assert!(!out.contains(&b'/'));
+        assert!(out.contains(&b'x'));
+    }
+
+    #[test]
+    fn strict_mode_stops_at_the_first_error() {
+        let mut validator = Utf8Validator::new();
+        validator.strict = true;
+        assert_eq!(
+            feed(&mut validator, b"ab\xffcd"),
+            (b"ab".into(), Err(InvalidUtf8Error { valid_up_to: 2 }))
+        );
+
+        assert_eq!(feed(&mut validator, b"\xe2"), (b"".into(), Ok(())));
+        assert_eq!(
+            feed(&mut validator, b"x"),
+            (b"".into(), Err(InvalidUtf8Error { valid_up_to: 0 }))
+        );
+    }
+}
@@ -0,0 +1,190 @@
+use crate::FlantermContext;
+use core::fmt::{self, Write};
+
+/// A rectangular region of a context with its own coordinates and cursor
+///
+/// Created with [`FlantermContext::viewport`]. Positions are relative to
+/// the top left corner of the region and output is clipped to it: text
+/// past the right edge is dropped up to the next line break, and lines
+/// below the bottom are dropped entirely, since the region does not
+/// scroll. Written text should not contain escape sequences; change
+/// attributes through [`context`](Self::context) instead.
+///
+/// The viewport moves the real cursor as it draws and leaves it wherever
+/// the last write ended.
+pub struct Viewport<'a> {
+    ctx: &'a mut FlantermContext,
+    x: usize,
+    y: usize,
+    w: usize,
+    h: usize,
+    col: usize,
+    row: usize,
+}
+
+impl FlantermContext {
+    /// Borrow the `w`x`h` region with its top left corner at (`x`, `y`) as a [`Viewport`]
+    ///
+    /// The region is cut down to the part that is on screen.
+    pub fn viewport(&mut self, x: usize, y: usize, w: usize, h: usize) -> Viewport<'_> {
+        let (cols, rows) = self.get_dimensions();
+        Viewport {
+            w: w.min(cols.saturating_sub(x)),
+            h: h.min(rows.saturating_sub(y)),
+            ctx: self,
+            x,
+            y,
+            col: 0,
+            row: 0,
+        }
+    }
+}
+
+impl Viewport<'_> {
+    /// Get the size of the region as (columns, rows)
+    pub fn size(&self) -> (usize, usize) {
+        (self.w, self.h)
+    }
+
+    /// Get the position of the region's top left corner on the screen
+    pub fn origin(&self) -> (usize, usize) {
+        (self.x, self.y)
+    }
+
+    /// Get the parent context, e.g. to change attributes
+    pub fn context(&mut self) -> &mut FlantermContext {
+        self.ctx
+    }
+
+    /// Get the viewport cursor as 0-indexed (column, row) within the region
+    ///
+    /// The column can be past the right edge after clipped output.
+    pub fn get_cursor_pos(&self) -> (usize, usize) {
+        (self.col, self.row)
+    }
+
+    /// Move the viewport cursor, clamping to the region
+    pub fn move_cursor(&mut self, col: usize, row: usize) {
+        self.col = col.min(self.w.saturating_sub(1));
+        self.row = row.min(self.h.saturating_sub(1));
+    }
+
+    /// Move the viewport cursor to the top left corner of the region
+    pub fn home(&mut self) {
+        self.move_cursor(0, 0);
+    }
+
+    /// Move the viewport cursor up `n` rows, stopping at the top
+    pub fn cursor_up(&mut self, n: usize) {
+        self.move_cursor(self.col, self.row.saturating_sub(n));
+    }
+
+    /// Move the viewport cursor down `n` rows, stopping at the bottom
+    pub fn cursor_down(&mut self, n: usize) {
+        self.move_cursor(self.col, self.row.saturating_add(n));
+    }
+
+    /// Move the viewport cursor right `n` columns, stopping at the right edge
+    pub fn cursor_forward(&mut self, n: usize) {
+        self.move_cursor(self.col.saturating_add(n), self.row);
+    }
+
+    /// Move the viewport cursor left `n` columns, stopping at the left edge
+    pub fn cursor_back(&mut self, n: usize) {
+        self.move_cursor(self.col.saturating_sub(n), self.row);
+    }
+
+    /// Erase the region in the current background color and move the cursor home
+    pub fn clear(&mut self) {
+        for row in 0..self.h {
+            self.blank(0, row, self.w);
+        }
+        self.home();
+    }
+
+    /// Erase from the cursor to the right edge of the region
+    pub fn erase_to_eol(&mut self) {
+        self.blank(self.col, self.row, self.w.saturating_sub(self.col));
+    }
+
+    /// Erase from the left edge of the region to the cursor, inclusive
+    pub fn erase_to_bol(&mut self) {
+        self.blank(0, self.row, (self.col + 1).min(self.w));
+    }
+
+    /// Erase the cursor's row of the region
+    pub fn erase_line(&mut self) {
+        self.blank(0, self.row, self.w);
+    }
+
+    /// Erase from the cursor to the end of the region
+    pub fn erase_below(&mut self) {
+        self.erase_to_eol();
+        for row in self.row + 1..self.h {
+            self.blank(0, row, self.w);
+        }
+    }
+
+    /// Erase from the start of the region to the cursor, inclusive
+    pub fn erase_above(&mut self) {
+        for row in 0..self.row.min(self.h) {
+            self.blank(0, row, self.w);
+        }
+        self.erase_to_bol();
+    }
+
+    /// Overwrite `n` cells from (`col`, `row`) with spaces, without moving the viewport cursor
+    fn blank(&mut self, col: usize, row: usize, n: usize) {
+        if row < self.h && n > 0 {
+            self.place(col, row);
+            let _ = self.ctx.write_char_n(' ', n);
+        }
+    }
+
+    /// Move the real cursor to a position in the region
+    fn place(&mut self, col: usize, row: usize) {
+        let _ = write!(self.ctx, "\x1b[{};{}H", self.y + row + 1, self.x + col + 1);
+    }
+}
+
+impl Write for Viewport<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        // Start of the visible text not yet written, which the real cursor is at
+        let mut run: Option<usize> = None;
+        for (i, ch) in s.char_indices() {
+            let width = FlantermContext::char_display_width(ch);
+            let visible = self.row < self.h && !ch.is_control() && self.col + width <= self.w;
+            if visible {
+                if run.is_none() {
+                    self.place(self.col, self.row);
+                    run = Some(i);
+                }
+            } else if let Some(start) = run.take() {
+                self.ctx.write_bytes(&s.as_bytes()[start..i]);
+            }
+
+            match ch {
+                '\n' => {
+                    self.col = 0;
+                    self.row += 1;
+                }
+                '\r' => self.col = 0,
+                '\t' => {
+                    let next = (self.col / 8 + 1) * 8;
+                    self.blank(
+                        self.col,
+                        self.row,
+                        next.min(self.w).saturating_sub(self.col),
+                    );
+                    self.col = next;
+                }
+                _ if ch.is_control() => {}
+                _ => self.col += width,
+            }
+        }
+        if let Some(start) = run {
+            self.ctx.write_bytes(&s.as_bytes()[start..]);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,94 @@
+use crate::FlantermContext;
+use crate::bindings::*;
+use core::cell::Cell;
+use core::slice;
+
+/// Output held back by `enable_write_buffer` until the next flush
+pub(crate) struct WriteBuffer {
+    buf: *mut u8,
+    cap: usize,
+    /// In a `Cell` so queries taking `&self` can drain the buffer first
+    len: Cell<usize>,
+}
+
+impl WriteBuffer {
+    /// Append `bytes`, draining first if they do not fit
+    ///
+    /// Writes larger than the whole buffer go straight to flanterm.
+    pub(crate) fn push(&self, ctx: *mut flanterm_context, bytes: &[u8]) {
+        let len = self.len.get();
+        if bytes.len() > self.cap - len {
+            self.drain(ctx);
+            if bytes.len() > self.cap {
+                write_raw(ctx, bytes);
+                return;
+            }
+        }
+        let len = self.len.get();
+        unsafe {
+            self.buf
+                .add(len)
+                .copy_from_nonoverlapping(bytes.as_ptr(), bytes.len());
+        }
+        self.len.set(len + bytes.len());
+    }
+
+    /// Pass everything buffered to flanterm in one call
+    fn drain(&self, ctx: *mut flanterm_context) {
+        let len = self.len.replace(0);
+        write_raw(ctx, unsafe { slice::from_raw_parts(self.buf, len) });
+    }
+}
+
+/// Write straight to flanterm
+pub(crate) fn write_raw(ctx: *mut flanterm_context, bytes: &[u8]) {
+    if bytes.is_empty() {
+        return;
+    }
+    unsafe {
+        flanterm_write(ctx, bytes.as_ptr() as *const i8, bytes.len());
+    }
+}
+
+impl FlantermContext {
+    /// Collect output in `buf` rather than passing each write to flanterm
+    ///
+    /// Writes are appended to `buf` and handed over in a single call on the
+    /// next [`flush`](Self::flush), or when the next write does not fit, so
+    /// a loop of small writes needs one `flush` afterwards to be sure it
+    /// is on screen. Anything that reads or changes
+    /// flanterm's state, such as [`get_cursor_pos`](Self::get_cursor_pos),
+    /// scrolling or palette changes, drains the buffer first so it sees
+    /// the output in order. A buffer set earlier is drained and replaced.
+    pub fn enable_write_buffer(&mut self, buf: &'static mut [u8]) {
+        self.drain_write_buffer();
+        self.write_buffer = Some(WriteBuffer {
+            buf: buf.as_mut_ptr(),
+            cap: buf.len(),
+            len: Cell::new(0),
+        });
+    }
+
+    /// Drain and remove the buffer set with [`enable_write_buffer`](Self::enable_write_buffer), handing it back
+    ///
+    /// While the context is disabled, anything still buffered is dropped.
+    pub fn disable_write_buffer(&mut self) -> Option<&'static mut [u8]> {
+        self.drain_write_buffer();
+        let wb = self.write_buffer.take()?;
+        Some(unsafe { slice::from_raw_parts_mut(wb.buf, wb.cap) })
+    }
+
+    /// Pass any buffered output to flanterm
+    ///
+    /// Does nothing while the context is disabled, so the buffered bytes
+    /// wait for the next drain after [`set_enabled`](Self::set_enabled)
+    /// turns output back on.
+    pub(crate) fn drain_write_buffer(&self) {
+        if let Some(wb) = &self.write_buffer
+            && !self.ctx.is_null()
+            && self.enabled
+        {
+            wb.drain(self.ctx);
+        }
+    }
+}
@@ -0,0 +1,23 @@
+mod common;
+
+use core::fmt::Write;
+
+use common::{HEIGHT, WIDTH, assert_cell};
+use flanterm_rs::Color;
+
+const BLUE: u32 = 0x0000ff;
+
+#[test]
+fn canvas_shows_behind_default_background() {
+    let mut framebuffer = common::framebuffer();
+    let canvas = vec![Color(BLUE); WIDTH * HEIGHT];
+
+    let mut term = common::builder(&mut framebuffer)
+        .canvas(canvas.as_ptr())
+        .build()
+        .unwrap();
+    term.write_str(" ").unwrap();
+    term.flush();
+
+    assert_cell(&term, 0, 0, BLUE);
+}
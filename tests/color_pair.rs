@@ -0,0 +1,23 @@
+mod common;
+
+use common::assert_cell;
+use flanterm_rs::{Color, ColorPair};
+
+const BACKGROUND: u32 = 0x203040;
+
+#[test]
+fn default_pair_follows_custom_defaults() {
+    let mut framebuffer = common::framebuffer();
+
+    let mut term = common::bump_builder(&mut framebuffer)
+        .default_bg(Color(BACKGROUND))
+        .build()
+        .unwrap();
+    ColorPair::new(7, 1).apply(&mut term);
+    ColorPair::DEFAULT.apply(&mut term);
+    term.write_bytes(b" ");
+    term.flush();
+
+    assert_cell(&term, 0, 0, BACKGROUND);
+    assert_eq!(term.current_attrs().and_then(|a| a.bg), None);
+}
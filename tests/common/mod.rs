@@ -0,0 +1,104 @@
+//! Framebuffer fixture shared by the integration tests
+#![allow(dead_code)]
+
+use core::ffi::c_void;
+use std::alloc::{Layout, alloc, dealloc};
+
+use flanterm_rs::{FlantermContext, FlantermContextBuilder};
+
+/// Framebuffer size; with no margin the built-in 8x16 font tiles it exactly,
+/// so cell (`col`, `row`) starts at pixel (`col * 8`, `row * 16`)
+pub const WIDTH: usize = 640;
+pub const HEIGHT: usize = 480;
+pub const GLYPH_WIDTH: usize = 8;
+pub const GLYPH_HEIGHT: usize = 16;
+
+/// Room in front of each allocation for its size, kept at malloc's alignment
+const HEADER: usize = 16;
+
+unsafe extern "C" fn test_malloc(size: usize) -> *mut c_void {
+    let layout = Layout::from_size_align(size + HEADER, HEADER).unwrap();
+    unsafe {
+        let base = alloc(layout);
+        if base.is_null() {
+            return base.cast();
+        }
+        base.cast::<usize>().write(size);
+        base.add(HEADER).cast()
+    }
+}
+
+unsafe extern "C" fn test_free(ptr: *mut c_void) {
+    if ptr.is_null() {
+        return;
+    }
+    unsafe {
+        let base = ptr.cast::<u8>().sub(HEADER);
+        let size = base.cast::<usize>().read();
+        dealloc(
+            base,
+            Layout::from_size_align(size + HEADER, HEADER).unwrap(),
+        );
+    }
+}
+
+/// A zeroed `WIDTH`x`HEIGHT` framebuffer
+///
+/// Declare it before the context drawing into it, so the context is dropped
+/// first.
+pub fn framebuffer() -> Vec<u32> {
+    vec![0; WIDTH * HEIGHT]
+}
+
+/// Start building a context on `framebuffer`
+///
+/// The context allocates from the heap rather than flanterm's bump
+/// allocator, so a test binary can create as many as it likes.
+pub fn builder(framebuffer: &mut [u32]) -> FlantermContextBuilder {
+    let mut builder = bump_builder(framebuffer);
+    builder.allocator(test_malloc, test_free);
+    builder
+}
+
+/// Start building a context on `framebuffer` that uses flanterm's bump allocator
+///
+/// Only one such context can exist per test binary. With heap allocation
+/// the bundled flanterm paints default-background cells from its (zeroed)
+/// sixel canvas rather than the default background color, so tests of
+/// custom default colors need this, as do tests of the wrapper's paths that
+/// require there to be no canvas at all.
+pub fn bump_builder(framebuffer: &mut [u32]) -> FlantermContextBuilder {
+    assert_eq!(framebuffer.len(), WIDTH * HEIGHT);
+    FlantermContextBuilder::new(framebuffer.as_mut_ptr(), WIDTH, HEIGHT, WIDTH * 4)
+}
+
+/// Build a context on `framebuffer` with default settings
+pub fn context(framebuffer: &mut [u32]) -> FlantermContext {
+    builder(framebuffer).build().unwrap()
+}
+
+/// Assert that every pixel of cell (`col`, `row`) in `pixels` is `color`
+pub fn assert_framebuffer_cell(pixels: &[u32], col: usize, row: usize, color: u32) {
+    for y in row * GLYPH_HEIGHT..(row + 1) * GLYPH_HEIGHT {
+        for x in col * GLYPH_WIDTH..(col + 1) * GLYPH_WIDTH {
+            assert_eq!(
+                pixels[y * WIDTH + x],
+                color,
+                "pixel ({x}, {y}) of cell ({col}, {row})"
+            );
+        }
+    }
+}
+
+/// Assert that every pixel of cell (`col`, `row`) reads back as `color` through `term`
+pub fn assert_cell(term: &FlantermContext, col: usize, row: usize, color: u32) {
+    for y in row * GLYPH_HEIGHT..(row + 1) * GLYPH_HEIGHT {
+        for x in col * GLYPH_WIDTH..(col + 1) * GLYPH_WIDTH {
+            assert_eq!(
+                term.get_pixel(x, y),
+                Some(color),
+                "pixel ({x}, {y}) of cell ({col}, {row})"
+            );
+        }
+    }
+}
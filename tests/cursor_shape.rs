@@ -0,0 +1,31 @@
+mod common;
+
+use std::sync::Mutex;
+
+use flanterm_rs::{CursorShape, EscapeInterceptor};
+
+static OFFERED: Mutex<Vec<Vec<u8>>> = Mutex::new(Vec::new());
+
+struct Recorder;
+
+impl EscapeInterceptor for Recorder {
+    fn on_escape(&mut self, seq: &[u8]) -> bool {
+        OFFERED.lock().unwrap().push(seq.into());
+        false
+    }
+}
+
+#[test]
+fn cursor_shape_only_reaches_the_interceptor() {
+    let mut framebuffer = common::framebuffer();
+
+    let mut term = common::context(&mut framebuffer);
+    term.set_cursor_shape(CursorShape::BarSteady);
+    term.set_interceptor(Box::leak(Box::new(Recorder)));
+    term.set_cursor_shape(CursorShape::Default);
+    term.flush();
+
+    assert_eq!(*OFFERED.lock().unwrap(), [b"\x1b[0 q"]);
+    // flanterm would have printed the `q`
+    assert_eq!(term.get_cursor_pos(), (0, 0));
+}
@@ -0,0 +1,25 @@
+mod common;
+
+use flanterm_rs::DecMode;
+
+#[test]
+fn alt_screen_mode_goes_through_the_wrapper() {
+    let mut framebuffer = common::framebuffer();
+
+    let mut term = common::context(&mut framebuffer);
+    term.set_dec_mode(DecMode::AltScreen, true);
+    assert!(term.in_alt_screen());
+    term.set_dec_mode(1049u16, false);
+    assert!(!term.in_alt_screen());
+}
+
+#[test]
+fn emulated_modes_are_recorded() {
+    let mut framebuffer = common::framebuffer();
+
+    let mut term = common::context(&mut framebuffer);
+    term.set_dec_mode(DecMode::OriginMode, true);
+    term.set_dec_mode(DecMode::AutoWrap, false);
+    assert!(term.origin_mode());
+    assert!(!term.auto_wrap());
+}
@@ -0,0 +1,23 @@
+mod common;
+
+use core::fmt::Write;
+
+use common::assert_cell;
+use flanterm_rs::Color;
+
+const BACKGROUND: u32 = 0x203040;
+
+#[test]
+fn custom_default_background_survives_sgr_reset() {
+    let mut framebuffer = common::framebuffer();
+
+    let mut term = common::bump_builder(&mut framebuffer)
+        .default_bg(Color(BACKGROUND))
+        .build()
+        .unwrap();
+    term.write_str(" \x1b[0m ").unwrap();
+    term.flush();
+
+    assert_cell(&term, 0, 0, BACKGROUND);
+    assert_cell(&term, 1, 0, BACKGROUND);
+}
@@ -0,0 +1,22 @@
+mod common;
+
+use common::{HEIGHT, WIDTH};
+
+#[test]
+fn erase_waits_for_flush_without_autoflush() {
+    let mut framebuffer = common::framebuffer();
+
+    // Only flanterm's bump allocator allows the fast path, see `bump_builder`
+    let mut term = common::bump_builder(&mut framebuffer).build().unwrap();
+    let background = term.get_pixel(WIDTH - 1, HEIGHT - 1);
+    term.write_bytes(b"\x1b[41m \x1b[0m");
+    term.flush();
+    let red = term.get_pixel(0, 0);
+    assert_ne!(red, background);
+
+    term.set_autoflush(false);
+    term.erase_screen();
+    assert_eq!(term.get_pixel(0, 0), red);
+    term.flush();
+    assert_eq!(term.get_pixel(0, 0), background);
+}
@@ -0,0 +1,28 @@
+mod common;
+
+use std::sync::Mutex;
+
+use flanterm_rs::EscapeInterceptor;
+
+static OFFERED: Mutex<Vec<Vec<u8>>> = Mutex::new(Vec::new());
+
+struct Recorder;
+
+impl EscapeInterceptor for Recorder {
+    fn on_escape(&mut self, seq: &[u8]) -> bool {
+        OFFERED.lock().unwrap().push(seq.into());
+        false
+    }
+}
+
+#[test]
+fn interceptor_sees_the_erase() {
+    let mut framebuffer = common::framebuffer();
+
+    // Only flanterm's bump allocator allows the fast path, see `bump_builder`
+    let mut term = common::bump_builder(&mut framebuffer).build().unwrap();
+    term.set_interceptor(Box::leak(Box::new(Recorder)));
+    term.erase_screen();
+
+    assert_eq!(*OFFERED.lock().unwrap(), [b"\x1b[2J"]);
+}
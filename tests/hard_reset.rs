@@ -0,0 +1,26 @@
+mod common;
+
+use common::{HEIGHT, WIDTH, assert_framebuffer_cell};
+
+#[test]
+fn hard_reset_reaches_the_front_buffer() {
+    let mut framebuffer = common::framebuffer();
+    let mut back_buffer = common::framebuffer();
+
+    let mut term = common::context(&mut framebuffer);
+    unsafe {
+        term.enable_double_buffer(back_buffer.as_mut_ptr(), back_buffer.len() * 4)
+            .unwrap();
+    }
+    term.write_bytes(b"AB");
+    term.flush();
+    term.hard_reset();
+    term.write_bytes(b"\x1b[47m  ");
+    term.flush();
+    let white = term.get_pixel(0, 0).unwrap();
+    assert_ne!(white, term.get_pixel(WIDTH - 1, HEIGHT - 1).unwrap());
+    drop(term);
+
+    assert_framebuffer_cell(&framebuffer, 1, 0, white);
+    assert!(framebuffer == back_buffer);
+}
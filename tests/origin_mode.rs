@@ -0,0 +1,21 @@
+mod common;
+
+#[test]
+fn home_and_clear_respect_origin_mode() {
+    let mut framebuffer = common::framebuffer();
+
+    let mut term = common::context(&mut framebuffer);
+    term.set_scroll_region(5, 10);
+    term.set_origin_mode(true);
+    term.move_cursor(3, 2);
+    term.home();
+    assert_eq!(term.get_cursor_pos(), (0, 5));
+
+    term.move_cursor(3, 2);
+    term.clear();
+    assert_eq!(term.get_cursor_pos(), (0, 5));
+
+    term.set_origin_mode(false);
+    term.home();
+    assert_eq!(term.get_cursor_pos(), (0, 0));
+}
@@ -0,0 +1,24 @@
+mod common;
+
+use common::{HEIGHT, WIDTH, assert_cell};
+
+#[test]
+fn restored_rapid_blink_keeps_the_normal_background() {
+    let mut framebuffer = common::framebuffer();
+
+    let mut term = common::context(&mut framebuffer);
+    let background = term.get_pixel(WIDTH - 1, HEIGHT - 1).unwrap();
+    term.set_rapid_blink();
+    assert!(
+        term.current_attrs()
+            .is_some_and(|a| a.rapid_blink && !a.blink)
+    );
+    term.push_attrs();
+    term.pop_attrs();
+    term.write_bytes(b" ");
+    term.flush();
+
+    // flanterm ignores SGR 6, so restoring it must not turn on the bright
+    // background of slow blink
+    assert_cell(&term, 0, 0, background);
+}
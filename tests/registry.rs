@@ -0,0 +1,19 @@
+mod common;
+
+use flanterm_rs::{RegistryError, register_global_context, unregister_global_context};
+
+#[test]
+fn rejected_context_is_handed_back() {
+    let mut first = common::framebuffer();
+    let mut second = common::framebuffer();
+
+    assert!(register_global_context("display", common::context(&mut first)).is_ok());
+    let Err((err, term)) = register_global_context("display", common::context(&mut second)) else {
+        panic!("duplicate name was accepted");
+    };
+    assert_eq!(err, RegistryError::AlreadyRegistered);
+    assert!(term.is_valid());
+
+    drop(term);
+    drop(unregister_global_context("display"));
+}
@@ -0,0 +1,29 @@
+mod common;
+
+extern crate std;
+
+use flanterm_rs::FlantermContext;
+use std::boxed::Box;
+use std::vec;
+
+#[test]
+fn context_does_not_hold_the_bitmap() {
+    assert!(core::mem::size_of::<FlantermContext>() < 2048);
+}
+
+#[test]
+fn bitmap_is_handed_back() {
+    let mut framebuffer = common::framebuffer();
+
+    let mut term = common::context(&mut framebuffer);
+    term.enable_selective_refresh(Box::leak(vec![0u8; 256].into_boxed_slice()));
+    term.write_bytes(b"\x1b[41m ");
+    term.flush();
+    term.selective_refresh();
+    common::assert_cell(&term, 0, 0, 0xaa0000);
+
+    let bitmap = term.disable_selective_refresh().unwrap();
+    assert_eq!(bitmap.len(), 256);
+    assert!(bitmap.iter().all(|&b| b == 0));
+    assert!(term.disable_selective_refresh().is_none());
+}
@@ -0,0 +1,36 @@
+mod common;
+
+use common::assert_cell;
+use flanterm_rs::{ColorSpec, TextAttributes};
+
+#[test]
+fn background_survives_a_full_attribute_set() {
+    let mut framebuffer = common::framebuffer();
+
+    let mut term = common::context(&mut framebuffer);
+    // Seven flags and two RGB colors make 17 parameters, one more than
+    // flanterm keeps from a single sequence
+    let attrs = TextAttributes {
+        bold: true,
+        dim: true,
+        italic: true,
+        underline: true,
+        blink: true,
+        hidden: true,
+        strikethrough: true,
+        fg: Some(ColorSpec::Rgb(0x11, 0x22, 0x33)),
+        bg: Some(ColorSpec::Rgb(0x44, 0x55, 0x66)),
+        ..TextAttributes::default()
+    };
+    term.apply_attrs(&attrs);
+    term.write_bytes(b" ");
+    // Restoring them adds a leading reset
+    term.push_attrs();
+    term.reset_format();
+    term.pop_attrs();
+    term.write_bytes(b" ");
+    term.flush();
+
+    assert_cell(&term, 0, 0, 0x445566);
+    assert_cell(&term, 1, 0, 0x445566);
+}
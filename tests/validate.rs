@@ -0,0 +1,18 @@
+use core::ptr::NonNull;
+
+use flanterm_rs::{FbInitError, FlantermContextBuilder};
+
+#[test]
+fn overflowing_width_is_an_invalid_pitch() {
+    let framebuffer = NonNull::<u32>::dangling().as_ptr();
+
+    let result = FlantermContextBuilder::new(framebuffer, usize::MAX / 2, 1, usize::MAX).build();
+
+    assert!(matches!(
+        result,
+        Err(FbInitError::InvalidPitch {
+            pitch: usize::MAX,
+            min_required: usize::MAX,
+        })
+    ));
+}
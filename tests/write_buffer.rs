@@ -0,0 +1,22 @@
+mod common;
+
+extern crate std;
+
+use std::boxed::Box;
+use std::vec;
+
+#[test]
+fn disabled_context_keeps_buffered_output() {
+    let mut framebuffer = common::framebuffer();
+
+    let mut term = common::context(&mut framebuffer);
+    term.enable_write_buffer(Box::leak(vec![0u8; 64].into_boxed_slice()));
+    term.write_bytes(b"AB");
+    term.set_enabled(false);
+    assert_eq!(term.get_cursor_pos(), (0, 0));
+    term.set_tab_width(4);
+    assert_eq!(term.get_cursor_pos(), (0, 0));
+
+    term.set_enabled(true);
+    assert_eq!(term.get_cursor_pos(), (2, 0));
+}